@@ -0,0 +1,272 @@
+use crate::commands::fs::parse_data_uri;
+use crate::commands::jobs::BackgroundJobs;
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::pictures::read_pictures;
+use crate::commands::write_options::TagWriteOptions;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgb, RgbImage};
+use lofty::picture::{MimeType, Picture};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Quality used when re-encoding without an explicit one
+const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+/// How artwork is prepared before it is embedded.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ArtworkOptions {
+    /// Longest side in pixels; larger images are scaled down
+    pub max_dimension: Option<u32>,
+    /// Re-encode as JPEG at this quality (1-100). Scaled images are always
+    /// re-encoded, at 90 unless set.
+    pub jpeg_quality: Option<u8>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ArtworkNormalizeResult {
+    pub path: String,
+    /// Total size of the embedded pictures before and after
+    #[serde(rename = "bytesBefore")]
+    pub bytes_before: usize,
+    #[serde(rename = "bytesAfter")]
+    pub bytes_after: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArtworkNormalizeProgress {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArtworkNormalizeComplete {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    pub cancelled: bool,
+    #[serde(rename = "bytesSaved")]
+    pub bytes_saved: usize,
+    pub results: Vec<ArtworkNormalizeResult>,
+}
+
+/// The image without alpha channel, transparent areas shown on white as
+/// viewers do, rather than on the black a plain conversion gives.
+fn flatten_onto_white(img: &DynamicImage) -> RgbImage {
+    if !img.color().has_alpha() {
+        return img.to_rgb8();
+    }
+    let rgba = img.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+/// Downscale and/or re-encode an image as JPEG. Returns `None` when the image
+/// is within `max_dimension` and re-encoding would not make it smaller.
+pub(crate) fn shrink_image(
+    bytes: &[u8],
+    options: &ArtworkOptions,
+) -> Result<Option<Vec<u8>>, String> {
+    if options.max_dimension.is_none() && options.jpeg_quality.is_none() {
+        return Ok(None);
+    }
+
+    let img =
+        image::load_from_memory(bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+    let resized = match options.max_dimension {
+        Some(max) if max > 0 && img.width().max(img.height()) > max => {
+            Some(img.resize(max, max, FilterType::Lanczos3))
+        }
+        _ => None,
+    };
+    if resized.is_none() && options.jpeg_quality.is_none() {
+        return Ok(None);
+    }
+    let is_resized = resized.is_some();
+    let img = resized.unwrap_or(img);
+
+    let quality = options
+        .jpeg_quality
+        .unwrap_or(DEFAULT_JPEG_QUALITY)
+        .clamp(1, 100);
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, quality)
+        .encode_image(&flatten_onto_white(&img))
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    if !is_resized && encoded.len() >= bytes.len() {
+        return Ok(None);
+    }
+    Ok(Some(encoded))
+}
+
+/// `pic` prepared per `options`, keeping its type and description.
+pub(crate) fn shrink_picture(
+    pic: &Picture,
+    options: &ArtworkOptions,
+) -> Result<Option<Picture>, String> {
+    Ok(shrink_image(pic.data(), options)?.map(|bytes| {
+        Picture::new_unchecked(
+            pic.pic_type(),
+            Some(MimeType::Jpeg),
+            pic.description().map(str::to_string),
+            bytes,
+        )
+    }))
+}
+
+/// A data URI image prepared per `options`; unchanged if there is nothing to do.
+pub(crate) fn shrink_data_uri(uri: &str, options: &ArtworkOptions) -> Result<String, String> {
+    use base64::Engine;
+
+    if uri.is_empty() {
+        return Ok(String::new());
+    }
+    let data = parse_data_uri(uri)?;
+    Ok(match shrink_image(&data.bytes, options)? {
+        Some(bytes) => format!(
+            "data:image/jpeg;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ),
+        None => uri.to_string(),
+    })
+}
+
+fn normalize_file(
+    recorder: &mut TagEditRecorder,
+    path: &Path,
+    options: &ArtworkOptions,
+) -> Result<(usize, usize), String> {
+    let pictures = read_pictures(path)?;
+    let before: usize = pictures.iter().map(|pic| pic.data().len()).sum();
+
+    let mut changed = false;
+    let mut normalized = Vec::with_capacity(pictures.len());
+    for pic in pictures {
+        match shrink_picture(&pic, options) {
+            Ok(Some(smaller)) => {
+                changed = true;
+                normalized.push(smaller);
+            }
+            // A picture that cannot be decoded is kept as it is
+            Ok(None) | Err(_) => normalized.push(pic),
+        }
+    }
+    if !changed {
+        return Ok((before, before));
+    }
+
+    let after = normalized.iter().map(|pic| pic.data().len()).sum();
+    recorder.write_pictures(path, normalized)?;
+    Ok((before, after))
+}
+
+fn run_normalization(
+    app: &AppHandle,
+    job_id: &str,
+    cancel: &AtomicBool,
+    paths: Vec<String>,
+    options: &ArtworkOptions,
+    write_options: TagWriteOptions,
+) -> ArtworkNormalizeComplete {
+    let total = paths.len();
+    let (mut recorder, journal_error) = match TagEditRecorder::begin(app, "Normalize artwork") {
+        Ok(recorder) => (
+            Some(recorder.with_write_options(write_options)),
+            String::new(),
+        ),
+        Err(e) => (None, e),
+    };
+    let mut results = Vec::with_capacity(total);
+
+    for (i, path) in paths.into_iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let _ = app.emit(
+            "artwork-normalize-progress",
+            ArtworkNormalizeProgress {
+                job_id: job_id.to_string(),
+                current: i + 1,
+                total,
+                path: path.clone(),
+            },
+        );
+
+        let mut result = ArtworkNormalizeResult {
+            path: path.clone(),
+            ..Default::default()
+        };
+        let outcome = match recorder.as_mut() {
+            Some(recorder) => normalize_file(recorder, Path::new(&path), options),
+            None => Err(journal_error.clone()),
+        };
+        match outcome {
+            Ok((before, after)) => {
+                result.bytes_before = before;
+                result.bytes_after = after;
+            }
+            Err(e) => result.error = Some(e),
+        }
+        results.push(result);
+    }
+
+    // Files written before a cancellation stay in the journal so they can be undone
+    if let Some(recorder) = recorder {
+        if let Err(e) = recorder.finish() {
+            let written = results
+                .iter_mut()
+                .filter(|r| r.error.is_none() && r.bytes_after != r.bytes_before);
+            for result in written {
+                result.error = Some(e.clone());
+            }
+        }
+    }
+
+    ArtworkNormalizeComplete {
+        job_id: job_id.to_string(),
+        cancelled: cancel.load(Ordering::Relaxed),
+        bytes_saved: results
+            .iter()
+            .map(|r| r.bytes_before.saturating_sub(r.bytes_after))
+            .sum(),
+        results,
+    }
+}
+
+/// Downscale / re-encode every embedded picture of `paths` in the background.
+/// Progress is emitted as `artwork-normalize-progress`, the results as
+/// `artwork-normalize-complete`. Returns the job id. `write_options` applies as
+/// in `write_audio_tags`.
+#[tauri::command]
+pub async fn start_artwork_normalization(
+    app: AppHandle,
+    jobs: State<'_, BackgroundJobs>,
+    paths: Vec<String>,
+    options: ArtworkOptions,
+    write_options: Option<TagWriteOptions>,
+) -> Result<String, String> {
+    if options.max_dimension.is_none() && options.jpeg_quality.is_none() {
+        return Err("Set a maximum dimension or a JPEG quality".to_string());
+    }
+    let write_options = write_options.unwrap_or_default();
+    write_options.validate()?;
+    let (job_id, cancel) = jobs.start("artwork");
+
+    let id = job_id.clone();
+    std::thread::spawn(move || {
+        let complete = run_normalization(&app, &id, &cancel, paths, &options, write_options);
+        app.state::<BackgroundJobs>().finish(&id);
+        let _ = app.emit("artwork-normalize-complete", complete);
+    });
+
+    Ok(job_id)
+}
@@ -0,0 +1,107 @@
+use lofty::config::ParseOptions;
+use lofty::file::{FileType, TaggedFile};
+use lofty::mp4::{Mp4Codec, Mp4File};
+use lofty::prelude::*;
+use std::fs::File;
+use std::path::Path;
+
+/// Technical properties of an audio file; numbers are 0 when unknown.
+#[derive(Debug, Clone, Default)]
+pub struct AudioProperties {
+    /// Audio bitrate in kbps
+    pub bitrate: i64,
+    /// Hz
+    pub sample_rate: i64,
+    /// Bits per sample; 0 for lossy codecs
+    pub bit_depth: i64,
+    pub channels: i64,
+    /// "MP3" | "AAC" | "ALAC" | "FLAC" | "Opus" | "Vorbis" | "PCM" | ...
+    pub codec: String,
+    /// "MPEG" | "MP4" | "FLAC" | "Ogg" | "WAV" | "AIFF" | ...
+    pub container: String,
+    /// Bytes
+    pub file_size: u64,
+    pub lossless: bool,
+    pub duration_ms: i64,
+}
+
+fn container_name(file_type: FileType) -> String {
+    match file_type {
+        FileType::Aac => "AAC",
+        FileType::Aiff => "AIFF",
+        FileType::Ape => "APE",
+        FileType::Flac => "FLAC",
+        FileType::Mpeg => "MPEG",
+        FileType::Mp4 => "MP4",
+        FileType::Mpc => "Musepack",
+        FileType::Opus | FileType::Vorbis | FileType::Speex => "Ogg",
+        FileType::Wav => "WAV",
+        FileType::WavPack => "WavPack",
+        _ => "",
+    }
+    .to_string()
+}
+
+/// The codec inside an MP4 file, which the generic properties do not tell.
+/// The tags were read already, so only the properties are parsed again.
+fn mp4_codec(path: &Path) -> Option<&'static str> {
+    let mut file = File::open(path).ok()?;
+    let mp4 = Mp4File::read_from(&mut file, ParseOptions::new().read_tags(false)).ok()?;
+    Some(match mp4.properties().codec() {
+        Mp4Codec::AAC => "AAC",
+        Mp4Codec::ALAC => "ALAC",
+        Mp4Codec::MP3 => "MP3",
+        Mp4Codec::FLAC => "FLAC",
+        _ => "",
+    })
+}
+
+/// (codec, lossless) of a file of the given type.
+fn codec(path: &Path, file_type: FileType) -> (String, bool) {
+    let (codec, lossless) = match file_type {
+        FileType::Aac => ("AAC", false),
+        // Compressed WAV / AIFF-C exist but are rare enough to treat as PCM
+        FileType::Aiff | FileType::Wav => ("PCM", true),
+        FileType::Ape => ("Monkey's Audio", true),
+        FileType::Flac => ("FLAC", true),
+        FileType::Mpeg => ("MP3", false),
+        FileType::Mp4 => {
+            let codec = mp4_codec(path).unwrap_or_default();
+            (codec, matches!(codec, "ALAC" | "FLAC"))
+        }
+        FileType::Mpc => ("Musepack", false),
+        FileType::Opus => ("Opus", false),
+        FileType::Vorbis => ("Vorbis", false),
+        FileType::Speex => ("Speex", false),
+        FileType::WavPack => ("WavPack", true),
+        _ => ("", false),
+    };
+    (codec.to_string(), lossless)
+}
+
+/// Properties of the already parsed `tagged` file at `path`.
+pub(crate) fn read_properties(path: &Path, tagged: &TaggedFile) -> AudioProperties {
+    let file_type = tagged.file_type();
+    let props = tagged.properties();
+    let (codec, lossless) = codec(path, file_type);
+
+    AudioProperties {
+        bitrate: props
+            .audio_bitrate()
+            .or(props.overall_bitrate())
+            .unwrap_or(0) as i64,
+        sample_rate: props.sample_rate().unwrap_or(0) as i64,
+        // A depth reported for a lossy stream is the decoder output, not the source
+        bit_depth: if lossless {
+            props.bit_depth().unwrap_or(0) as i64
+        } else {
+            0
+        },
+        channels: props.channels().unwrap_or(0) as i64,
+        codec,
+        container: container_name(file_type),
+        file_size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        lossless,
+        duration_ms: props.duration().as_millis() as i64,
+    }
+}
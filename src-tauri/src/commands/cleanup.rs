@@ -0,0 +1,674 @@
+use crate::commands::fs::{read_tags_for_edit, AudioTags, TagWriteResult, MULTI_VALUE_SEPARATOR};
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::tag_batch::{TagWriteProgress, MULTI_VALUE_FIELDS};
+use crate::commands::tag_diff::{diff_against_file, to_tag_diff, TagDiff};
+use crate::commands::write_options::TagWriteOptions;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+/// Fields a rule applies to unless it names its own
+const DEFAULT_FIELDS: &[&str] = &[
+    "title",
+    "artist",
+    "albumArtist",
+    "album",
+    "genre",
+    "composer",
+    "conductor",
+    "lyricist",
+    "remixer",
+    "grouping",
+    "label",
+    "publisher",
+];
+
+/// Words title case leaves in lower case unless they start or end the text
+const DEFAULT_TITLE_CASE_EXCEPTIONS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "the", "to", "vs.", "with", "feat.",
+];
+
+/// Full-width forms of the half-width katakana block U+FF61..=U+FF9F, in order
+const HALF_WIDTH_KANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Katakana that take a dakuten (voiced form = code point + 1)
+const VOICEABLE_KANA: &str = "カキクケコサシスセソタチツテトハヒフヘホ";
+
+/// Katakana that take a handakuten (semi-voiced form = code point + 2)
+const SEMI_VOICEABLE_KANA: &str = "ハヒフヘホ";
+
+const HALF_WIDTH_DAKUTEN: char = 'ﾞ';
+const HALF_WIDTH_HANDAKUTEN: char = 'ﾟ';
+
+/// Keywords introducing guest artists, in a title or an artist
+const FEATURING: &str = r"(?:feat\.?|ft\.?|featuring)";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CleanupRule {
+    /// "trim" | "collapseSpaces" | "titleCase" | "halfWidthAscii" |
+    /// "fullWidthAscii" | "fullWidthKana" | "halfWidthKana" | "featuring" | "replace"
+    pub kind: String,
+    /// `AudioTags` fields to clean up; a common set of text fields if unset.
+    /// Ignored by "featuring", which always moves guests from title to artist.
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    /// titleCase: words written exactly as given (e.g. "of", "DJ", "iTunes"),
+    /// except that lower-case ones are capitalized at the start or end of the
+    /// text; small English words if unset
+    #[serde(default)]
+    pub exceptions: Option<Vec<String>>,
+    /// titleCase: also lower-case everything after the first letter of a word
+    #[serde(default)]
+    pub lowercase_rest: bool,
+    /// featuring: keyword put before the guests in the artist (default "feat.")
+    #[serde(default)]
+    pub keyword: Option<String>,
+    /// replace: regular expression to look for
+    #[serde(default)]
+    pub find: Option<String>,
+    /// replace: replacement text; `$1`, `${name}` etc. refer to capture groups
+    #[serde(default)]
+    pub replace: Option<String>,
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+enum Transform {
+    Trim,
+    CollapseSpaces(Regex),
+    TitleCase {
+        exceptions: Vec<String>,
+        lowercase_rest: bool,
+    },
+    HalfWidthAscii,
+    FullWidthAscii,
+    FullWidthKana,
+    HalfWidthKana,
+    Featuring {
+        keyword: String,
+        bracketed: Regex,
+        trailing: Regex,
+        inline: Regex,
+    },
+    Replace {
+        regex: Regex,
+        replace: String,
+    },
+}
+
+struct CompiledRule {
+    transform: Transform,
+    fields: Vec<String>,
+}
+
+fn build_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+impl CompiledRule {
+    fn compile(rule: &CleanupRule) -> Result<Self, String> {
+        let transform = match rule.kind.as_str() {
+            "trim" => Transform::Trim,
+            // Runs of horizontal whitespace; line breaks in multi-line fields stay
+            "collapseSpaces" => Transform::CollapseSpaces(build_regex(r"[^\S\r\n]{2,}", false)?),
+            "titleCase" => Transform::TitleCase {
+                exceptions: rule.exceptions.clone().unwrap_or_else(|| {
+                    DEFAULT_TITLE_CASE_EXCEPTIONS
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect()
+                }),
+                lowercase_rest: rule.lowercase_rest,
+            },
+            "halfWidthAscii" => Transform::HalfWidthAscii,
+            "fullWidthAscii" => Transform::FullWidthAscii,
+            "fullWidthKana" => Transform::FullWidthKana,
+            "halfWidthKana" => Transform::HalfWidthKana,
+            "featuring" => Transform::Featuring {
+                keyword: rule
+                    .keyword
+                    .clone()
+                    .filter(|k| !k.trim().is_empty())
+                    .unwrap_or_else(|| "feat.".to_string()),
+                bracketed: build_regex(
+                    &format!(r"\s*[(\[]\s*{}\s+([^)\]]+?)\s*[)\]]", FEATURING),
+                    true,
+                )?,
+                trailing: build_regex(&format!(r"\s+{}\s+(.+?)\s*$", FEATURING), true)?,
+                inline: build_regex(&format!(r"\s+{}\s+", FEATURING), true)?,
+            },
+            "replace" => {
+                let find = rule.find.as_deref().unwrap_or_default();
+                if find.is_empty() {
+                    return Err("Replace rule must not be empty".to_string());
+                }
+                Transform::Replace {
+                    regex: build_regex(find, rule.case_insensitive)?,
+                    replace: rule.replace.clone().unwrap_or_default(),
+                }
+            }
+            other => return Err(format!("Unknown cleanup rule: {}", other)),
+        };
+        let fields = match &rule.fields {
+            Some(fields) if !fields.is_empty() => fields.clone(),
+            _ => DEFAULT_FIELDS.iter().map(|s| s.to_string()).collect(),
+        };
+        Ok(CompiledRule { transform, fields })
+    }
+
+    /// Apply the rule to one text value.
+    fn apply_text(&self, text: &str) -> String {
+        match &self.transform {
+            Transform::Trim => text.trim().to_string(),
+            Transform::CollapseSpaces(regex) => regex.replace_all(text, " ").into_owned(),
+            Transform::TitleCase {
+                exceptions,
+                lowercase_rest,
+            } => title_case(text, exceptions, *lowercase_rest),
+            Transform::HalfWidthAscii => text.chars().map(half_width_ascii).collect(),
+            Transform::FullWidthAscii => text.chars().map(full_width_ascii).collect(),
+            Transform::FullWidthKana => full_width_kana(text),
+            Transform::HalfWidthKana => half_width_kana(text),
+            Transform::Featuring { .. } => text.to_string(),
+            Transform::Replace { regex, replace } => {
+                regex.replace_all(text, replace.as_str()).into_owned()
+            }
+        }
+    }
+
+    fn apply(&self, fields: &mut Map<String, Value>) -> Result<(), String> {
+        if let Transform::Featuring {
+            keyword,
+            bracketed,
+            trailing,
+            inline,
+        } = &self.transform
+        {
+            move_featuring(fields, keyword, bracketed, trailing, inline);
+            return Ok(());
+        }
+        for field in &self.fields {
+            transform_field(fields, field, |text| self.apply_text(text))?;
+        }
+        Ok(())
+    }
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Transform one field. A multi-value field's display string and value list
+/// are transformed together so that the writer keeps using the list.
+fn transform_field(
+    fields: &mut Map<String, Value>,
+    field: &str,
+    transform: impl Fn(&str) -> String,
+) -> Result<(), String> {
+    let current = fields
+        .get(field)
+        .ok_or_else(|| format!("Unknown tag field: {}", field))?
+        .clone();
+
+    if let Some((_, list)) = MULTI_VALUE_FIELDS
+        .iter()
+        .find(|(single, _)| *single == field)
+    {
+        let values = fields.get(*list).map(string_list).unwrap_or_default();
+        if !values.is_empty()
+            && Some(values.join(MULTI_VALUE_SEPARATOR).as_str()) == current.as_str()
+        {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| transform(v))
+                .filter(|v| !v.is_empty())
+                .collect();
+            fields.insert(
+                field.to_string(),
+                Value::String(values.join(MULTI_VALUE_SEPARATOR)),
+            );
+            fields.insert(list.to_string(), Value::from(values));
+            return Ok(());
+        }
+    }
+
+    let transformed = match current {
+        Value::String(text) => Value::String(transform(&text)),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|v| match v {
+                    Value::String(text) => Value::String(transform(&text)),
+                    other => other,
+                })
+                .collect(),
+        ),
+        _ => return Err(format!("Not a text field: {}", field)),
+    };
+    fields.insert(field.to_string(), transformed);
+    Ok(())
+}
+
+fn capitalize(word: &str, lowercase_rest: bool) -> String {
+    let mut result = String::with_capacity(word.len());
+    let mut seen_letter = false;
+    for c in word.chars() {
+        if !seen_letter && c.is_alphabetic() {
+            seen_letter = true;
+            result.extend(c.to_uppercase());
+        } else if seen_letter && lowercase_rest {
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Capitalize every word except `exceptions`, which are written exactly as
+/// given. Only a lower-case exception (a small word such as "of") is still
+/// capitalized when it starts or ends the text; "iTunes" stays "iTunes".
+fn title_case(text: &str, exceptions: &[String], lowercase_rest: bool) -> String {
+    let words: Vec<&str> = text.split(' ').collect();
+    let last = words.len().saturating_sub(1);
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            match exceptions
+                .iter()
+                .find(|e| e.to_lowercase() == word.to_lowercase())
+            {
+                Some(exception)
+                    if (i == 0 || i == last) && !exception.chars().any(char::is_uppercase) =>
+                {
+                    capitalize(exception, false)
+                }
+                Some(exception) => exception.clone(),
+                None => capitalize(word, lowercase_rest),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Full-width ASCII (U+FF01..=U+FF5E) and the ideographic space to ASCII.
+fn half_width_ascii(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Printable ASCII to its full-width form, the space to the ideographic space.
+fn full_width_ascii(c: char) -> char {
+    match c {
+        ' ' => '\u{3000}',
+        '!'..='~' => char::from_u32(c as u32 + 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Half-width katakana to full-width, merging (han)dakuten into the kana before them.
+fn full_width_kana(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !('\u{FF61}'..='\u{FF9F}').contains(&c) {
+            result.push(c);
+            continue;
+        }
+        let offset = (c as u32 - 0xFF61) as usize;
+        let Some(full) = HALF_WIDTH_KANA.chars().nth(offset) else {
+            result.push(c);
+            continue;
+        };
+        let combined = match (c, result.chars().last()) {
+            (HALF_WIDTH_DAKUTEN, Some('ウ')) => Some('ヴ'),
+            (HALF_WIDTH_DAKUTEN, Some(prev)) if VOICEABLE_KANA.contains(prev) => {
+                char::from_u32(prev as u32 + 1)
+            }
+            (HALF_WIDTH_HANDAKUTEN, Some(prev)) if SEMI_VOICEABLE_KANA.contains(prev) => {
+                char::from_u32(prev as u32 + 2)
+            }
+            _ => None,
+        };
+        match combined {
+            Some(kana) => {
+                result.pop();
+                result.push(kana);
+            }
+            None => result.push(full),
+        }
+    }
+    result
+}
+
+/// Full-width katakana to half-width; voiced kana become kana + (han)dakuten.
+fn half_width_kana(text: &str) -> String {
+    let half = |full: char| {
+        HALF_WIDTH_KANA
+            .chars()
+            .position(|k| k == full)
+            .and_then(|i| char::from_u32(0xFF61 + i as u32))
+    };
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        // Only katakana and the prolonged sound mark; punctuation such as 「」
+        // is shared with kanji and hiragana text
+        if !('\u{30A1}'..='\u{30FC}').contains(&c) {
+            result.push(c);
+            continue;
+        }
+        let base = |offset: u32| char::from_u32(c as u32 - offset);
+        if c == 'ヴ' {
+            result.extend(['ｳ', HALF_WIDTH_DAKUTEN]);
+        } else if let Some(h) = half(c) {
+            result.push(h);
+        } else if let Some(b) = base(1).filter(|b| VOICEABLE_KANA.contains(*b)) {
+            result.extend(half(b));
+            result.push(HALF_WIDTH_DAKUTEN);
+        } else if let Some(b) = base(2).filter(|b| SEMI_VOICEABLE_KANA.contains(*b)) {
+            result.extend(half(b));
+            result.push(HALF_WIDTH_HANDAKUTEN);
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// The names credited in `text`, split at featuring keywords and the usual
+/// separators, in lower case.
+fn credited_names(text: &str, keyword: &str, inline: &Regex) -> Vec<String> {
+    let keyword = format!(" {} ", keyword);
+    inline
+        .split(text)
+        .flat_map(|part| part.split(keyword.as_str()))
+        .flat_map(|part| part.split([';', ',', '&', '/']))
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Move "(feat. X)" / "feat. X" from the title to the artist and write every
+/// featuring keyword in the artist as `keyword`.
+fn move_featuring(
+    fields: &mut Map<String, Value>,
+    keyword: &str,
+    bracketed: &Regex,
+    trailing: &Regex,
+    inline: &Regex,
+) {
+    let text = |field: &str| {
+        fields
+            .get(field)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let title = text("title");
+    let original_artist = text("artist");
+    let mut artist = original_artist.clone();
+    if artist.is_empty() {
+        return;
+    }
+    let normalize = |text: &str| {
+        let text = bracketed.replace_all(text, |caps: &regex::Captures| {
+            format!(" {} {}", keyword, &caps[1])
+        });
+        inline
+            .replace_all(&text, |_: &regex::Captures| format!(" {} ", keyword))
+            .into_owned()
+    };
+    artist = normalize(&artist);
+
+    let guests = bracketed
+        .captures(&title)
+        .or_else(|| trailing.captures(&title))
+        .map(|caps| (caps[0].to_string(), caps[1].trim().to_string()));
+    if let Some((matched, guests)) = guests {
+        // Compared by name: "Ann" is not credited by "Joanna"
+        let credited = credited_names(&artist, keyword, inline);
+        let missing = credited_names(&guests, keyword, inline)
+            .iter()
+            .any(|guest| !credited.contains(guest));
+        if missing {
+            artist = format!("{} {} {}", artist, keyword, guests);
+        }
+        let title = title.replacen(&matched, "", 1).trim().to_string();
+        fields.insert("title".to_string(), Value::String(title));
+    }
+
+    if artist != original_artist {
+        // Keep the value list in line with the edited display string
+        let values: Vec<String> = artist
+            .split(';')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        fields.insert("artist".to_string(), Value::String(artist));
+        fields.insert("artists".to_string(), Value::from(values));
+    }
+}
+
+fn compile_rules(rules: &[CleanupRule]) -> Result<Vec<CompiledRule>, String> {
+    if rules.is_empty() {
+        return Err("No cleanup rules given".to_string());
+    }
+    rules.iter().map(CompiledRule::compile).collect()
+}
+
+/// `tags` with every rule applied in order.
+fn clean_tags(tags: &AudioTags, rules: &[CompiledRule]) -> Result<AudioTags, String> {
+    let mut value = serde_json::to_value(tags).map_err(|e| e.to_string())?;
+    let fields = value
+        .as_object_mut()
+        .ok_or("AudioTags must serialize to an object")?;
+    for rule in rules {
+        rule.apply(fields)?;
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+fn cleaned_file(path: &Path, rules: &[CompiledRule]) -> Result<AudioTags, String> {
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    // Cover art is not loaded, so the pictures stay as-is
+    clean_tags(&read_tags_for_edit(path, false), rules)
+}
+
+/// Dry run of `apply_tag_cleanup`: returns one diff per file.
+#[tauri::command]
+pub async fn preview_tag_cleanup(
+    paths: Vec<String>,
+    rules: Vec<CleanupRule>,
+) -> Result<Vec<TagDiff>, String> {
+    let rules = compile_rules(&rules)?;
+    // Reject unknown fields up front rather than once per file
+    clean_tags(&AudioTags::default(), &rules)?;
+
+    Ok(paths
+        .iter()
+        .map(|path| {
+            let file_path = Path::new(path);
+            let result = cleaned_file(file_path, &rules)
+                .and_then(|cleaned| diff_against_file(file_path, &cleaned));
+            to_tag_diff(path, result)
+        })
+        .collect())
+}
+
+fn clean_file(
+    recorder: &mut TagEditRecorder,
+    path: &Path,
+    rules: &[CompiledRule],
+) -> Result<(), String> {
+    let cleaned = cleaned_file(path, rules)?;
+    if diff_against_file(path, &cleaned)?.is_empty() {
+        return Ok(());
+    }
+    recorder.write(path, &cleaned)
+}
+
+/// Apply cleanup `rules` in order to the tags of every file and write the ones
+/// that change like `write_audio_tags` does. Emits `tag-write-progress` after
+/// each file.
+#[tauri::command]
+pub async fn apply_tag_cleanup(
+    app: AppHandle,
+    paths: Vec<String>,
+    rules: Vec<CleanupRule>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<TagWriteResult>, String> {
+    let rules = compile_rules(&rules)?;
+    clean_tags(&AudioTags::default(), &rules)?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let total = paths.len();
+    let mut results = Vec::with_capacity(total);
+    let mut recorder = TagEditRecorder::begin(&app, &format!("Clean up {} file(s)", total))?
+        .with_write_options(options);
+
+    for (i, path) in paths.iter().enumerate() {
+        let result = clean_file(&mut recorder, Path::new(path), &rules);
+        results.push(TagWriteResult::from_result(path, result));
+
+        let _ = app.emit(
+            "tag-write-progress",
+            TagWriteProgress {
+                current: i + 1,
+                total,
+                path: path.clone(),
+            },
+        );
+    }
+
+    recorder.finish_with_results(&mut results);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(value: Value) -> CompiledRule {
+        let rule: CleanupRule = serde_json::from_value(value).unwrap();
+        CompiledRule::compile(&rule).unwrap()
+    }
+
+    fn featuring(title: &str, artist: &str) -> (String, String, Value) {
+        let mut fields = Map::new();
+        fields.insert("title".to_string(), Value::from(title));
+        fields.insert("artist".to_string(), Value::from(artist));
+        rule(serde_json::json!({ "kind": "featuring" }))
+            .apply(&mut fields)
+            .unwrap();
+        (
+            fields["title"].as_str().unwrap().to_string(),
+            fields["artist"].as_str().unwrap().to_string(),
+            fields.get("artists").cloned().unwrap_or(Value::Null),
+        )
+    }
+
+    #[test]
+    fn title_case_keeps_exceptions_as_given() {
+        let exceptions = vec!["iTunes".to_string(), "of".to_string()];
+        assert_eq!(
+            title_case("itunes session of", &exceptions, false),
+            "iTunes Session Of"
+        );
+        assert_eq!(
+            title_case("live from itunes", &exceptions, false),
+            "Live From iTunes"
+        );
+        let defaults = rule(serde_json::json!({ "kind": "titleCase" }));
+        assert_eq!(defaults.apply_text("of mice and men"), "Of Mice and Men");
+    }
+
+    #[test]
+    fn full_width_kana_merges_sound_marks() {
+        assert_eq!(full_width_kana("ｶﾞｷﾞﾊﾟｳﾞｧｰ"), "ガギパヴァー");
+        assert_eq!(full_width_kana("東京ｽｶｲﾂﾘｰ"), "東京スカイツリー");
+        assert_eq!(full_width_kana("｢ﾃｽﾄ｣"), "「テスト」");
+    }
+
+    #[test]
+    fn half_width_kana_splits_sound_marks() {
+        assert_eq!(half_width_kana("ガギパヴァー"), "ｶﾞｷﾞﾊﾟｳﾞｧｰ");
+        // Punctuation shared with kanji and hiragana text is left alone
+        assert_eq!(half_width_kana("「テスト」"), "「ﾃｽﾄ」");
+    }
+
+    #[test]
+    fn kana_width_round_trips() {
+        for text in [
+            "ガギグゲゴ パピプペポ",
+            "ヴァイオリン",
+            "東京スカイツリー",
+            "ABC 123",
+        ] {
+            assert_eq!(full_width_kana(&half_width_kana(text)), text);
+        }
+        for text in ["ｶﾞｷﾞﾊﾟｳﾞｧｰ", "ﾊﾞｲｵﾘﾝ", "ABC 123"] {
+            assert_eq!(half_width_kana(&full_width_kana(text)), text);
+        }
+    }
+
+    #[test]
+    fn featuring_moves_bracketed_guests_to_artist() {
+        let (title, artist, artists) = featuring("Song (feat. B)", "A");
+        assert_eq!(title, "Song");
+        assert_eq!(artist, "A feat. B");
+        assert_eq!(artists, serde_json::json!(["A feat. B"]));
+    }
+
+    #[test]
+    fn featuring_moves_trailing_guests_to_artist() {
+        let (title, artist, _) = featuring("Song ft. B & C", "A");
+        assert_eq!(title, "Song");
+        assert_eq!(artist, "A feat. B & C");
+    }
+
+    #[test]
+    fn featuring_does_not_repeat_guests_already_credited() {
+        let (title, artist, _) = featuring("Song [Featuring B]", "A ft. B");
+        assert_eq!(title, "Song");
+        assert_eq!(artist, "A feat. B");
+    }
+
+    #[test]
+    fn featuring_compares_whole_names() {
+        let (title, artist, _) = featuring("Song (feat. Ann)", "Joanna");
+        assert_eq!(title, "Song");
+        assert_eq!(artist, "Joanna feat. Ann");
+
+        let (_, artist, _) = featuring("Song (feat. Ann & Bo)", "Bo, Ann");
+        assert_eq!(artist, "Bo, Ann");
+    }
+
+    #[test]
+    fn featuring_leaves_tracks_without_artist_alone() {
+        let (title, artist, artists) = featuring("Song (feat. B)", "");
+        assert_eq!(title, "Song (feat. B)");
+        assert_eq!(artist, "");
+        assert_eq!(artists, Value::Null);
+    }
+}
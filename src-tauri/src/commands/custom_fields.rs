@@ -0,0 +1,193 @@
+use crate::commands::fs::MP4_FREEFORM_PREFIX;
+use crate::commands::id3v2;
+use lofty::file::FileType;
+use lofty::id3::v2::{Frame, Id3v2Tag};
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::{ItemKey, ItemValue, Tag, TagItem, TagType};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Fields lofty has no key for (`DJ_CUE`, `OCCASION`, ...), keyed by
+/// name: the TXXX description in ID3v2, the field name in Vorbis comments and
+/// APE, and the iTunes freeform atom name in MP4.
+pub type CustomFields = BTreeMap<String, String>;
+
+/// Native names of modeled fields that lofty has no `ItemKey` for (MP4 freeform
+/// atoms, ratings and play counts)
+const RESERVED_NAMES: &[&str] = &[
+    "replaygain_track_gain",
+    "replaygain_track_peak",
+    "replaygain_album_gain",
+    "replaygain_album_peak",
+    "MusicBrainz Track Id",
+    "MusicBrainz Album Id",
+    "MusicBrainz Artist Id",
+    "MusicBrainz Release Group Id",
+    "ORIGINALDATE",
+    "CATALOGNUMBER",
+    "BARCODE",
+    "LABEL",
+    "CONDUCTOR",
+    "LYRICIST",
+    "REMIXER",
+    "MEDIA",
+    "RATING",
+    "FMPS_RATING",
+    "rate",
+    "PLAYCOUNT",
+    "FMPS_PLAYCOUNT",
+    "LOVED",
+];
+
+fn is_reserved(name: &str) -> bool {
+    RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(name))
+}
+
+/// Whether lofty knows `key`. Known keys (ENCODER, MOOD, ...) mean something
+/// to lofty and other players, so only unknown ones are custom fields, in every
+/// tag format alike.
+fn is_known(key: &ItemKey) -> bool {
+    !matches!(key, ItemKey::Unknown(_))
+}
+
+/// Custom field name of an item of the generic tag, if it is one.
+fn custom_name(tag_type: TagType, key: &ItemKey) -> Option<String> {
+    if is_known(key) {
+        return None;
+    }
+    let native = key.map_key(tag_type, true)?;
+    match tag_type {
+        TagType::Mp4Ilst => {
+            // Only iTunes freeform atoms; other atoms are not key/value pairs
+            let name = native.strip_prefix(MP4_FREEFORM_PREFIX)?;
+            (!is_reserved(name)).then(|| name.to_string())
+        }
+        TagType::Id3v2 => None,
+        _ => (!is_reserved(native)).then(|| native.to_string()),
+    }
+}
+
+/// Key of the item named `name` in a non-ID3v2 tag of `tag_type`.
+pub(crate) fn item_key(tag_type: TagType, name: &str) -> ItemKey {
+    match tag_type {
+        TagType::Mp4Ilst => {
+            ItemKey::from_key(tag_type, &format!("{}{}", MP4_FREEFORM_PREFIX, name))
+        }
+        _ => ItemKey::from_key(tag_type, name),
+    }
+}
+
+/// Reject names that are empty, malformed, or belong to a field lofty knows.
+pub(crate) fn validate(tag_type: TagType, fields: &CustomFields) -> Result<(), String> {
+    for name in fields.keys() {
+        if name.trim().is_empty() || name.contains('=') || name.chars().any(char::is_control) {
+            return Err(format!("Invalid custom field name: {:?}", name));
+        }
+        if is_reserved(name) || is_known(&item_key(tag_type, name)) {
+            return Err(format!(
+                "{} is a standard field and cannot be set as a custom field",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Custom fields of a non-ID3v2 tag.
+pub(crate) fn from_tag(tag: &Tag) -> CustomFields {
+    let tag_type = tag.tag_type();
+    let mut fields = CustomFields::new();
+    for item in tag.items() {
+        if let (Some(name), ItemValue::Text(value)) =
+            (custom_name(tag_type, item.key()), item.value())
+        {
+            // Repeated keys keep their first value
+            fields.entry(name).or_insert_with(|| value.clone());
+        }
+    }
+    fields
+}
+
+/// Make the custom fields of a non-ID3v2 tag exactly `fields`, leaving every other item alone.
+pub(crate) fn apply_to_tag(tag: &mut Tag, fields: &CustomFields) {
+    let tag_type = tag.tag_type();
+    let stale: Vec<ItemKey> = tag
+        .items()
+        .filter(|item| {
+            custom_name(tag_type, item.key()).is_some_and(|name| !fields.contains_key(&name))
+        })
+        .map(|item| item.key().clone())
+        .collect();
+    for key in stale {
+        tag.remove_key(&key);
+    }
+
+    for (name, value) in fields {
+        let key = item_key(tag_type, name);
+        if value.is_empty() {
+            tag.remove_key(&key);
+        } else {
+            tag.insert(TagItem::new(key, ItemValue::Text(value.clone())));
+        }
+    }
+}
+
+/// TXXX frames lofty does not map to a known key.
+pub(crate) fn from_id3v2(tag: &Id3v2Tag) -> CustomFields {
+    let mut fields = CustomFields::new();
+    for frame in tag {
+        if let Frame::UserText(text) = frame {
+            let known = is_known(&ItemKey::from_key(TagType::Id3v2, &text.description));
+            if !known && !is_reserved(&text.description) {
+                fields
+                    .entry(text.description.to_string())
+                    .or_insert_with(|| text.content.to_string());
+            }
+        }
+    }
+    fields
+}
+
+/// Make the custom TXXX fields of an ID3v2 tag exactly `fields`.
+pub(crate) fn apply_to_id3v2(tag: &mut Id3v2Tag, fields: &CustomFields) {
+    let stale: Vec<String> = from_id3v2(tag)
+        .into_keys()
+        .filter(|name| !fields.contains_key(name))
+        .collect();
+    for name in stale {
+        tag.remove_user_text(&name);
+    }
+    for (name, value) in fields {
+        if value.is_empty() {
+            tag.remove_user_text(name);
+        } else {
+            tag.insert_user_text(name.clone(), value.clone());
+        }
+    }
+}
+
+/// Custom TXXX fields of an ID3v2-tagged file.
+pub(crate) fn read_id3v2(path: &Path, file_type: FileType) -> Result<CustomFields, String> {
+    Ok(id3v2::read_tag(path, file_type)?
+        .as_ref()
+        .map(from_id3v2)
+        .unwrap_or_default())
+}
+
+/// Custom fields of the file, whatever tag format it uses.
+pub(crate) fn read_custom_fields(path: &Path) -> Result<CustomFields, String> {
+    let tagged = Probe::open(path)
+        .and_then(|p| p.read())
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    if id3v2::uses_id3v2(tagged.file_type()) {
+        return read_id3v2(path, tagged.file_type());
+    }
+    Ok(tagged
+        .primary_tag()
+        .or_else(|| tagged.first_tag())
+        .map(from_tag)
+        .unwrap_or_default())
+}
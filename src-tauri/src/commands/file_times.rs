@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Access and modification time of a file, taken before a tag write.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SavedTimes {
+    accessed: SystemTime,
+    modified: SystemTime,
+}
+
+impl SavedTimes {
+    pub(crate) fn read(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        Some(SavedTimes {
+            accessed: meta.accessed().ok()?,
+            modified: meta.modified().ok()?,
+        })
+    }
+
+    /// Put the saved times back on `path`.
+    pub(crate) fn restore(&self, path: &Path) -> Result<(), String> {
+        let file = fs::File::options()
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+        file.set_times(
+            fs::FileTimes::new()
+                .set_accessed(self.accessed)
+                .set_modified(self.modified),
+        )
+        .map_err(|e| format!("Failed to restore file times: {}", e))
+    }
+
+    pub(crate) fn modified_millis(&self) -> u64 {
+        to_millis(self.modified)
+    }
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Modification time of `path` in milliseconds since the epoch, 0 if unknown.
+pub(crate) fn modified_millis(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(to_millis)
+        .unwrap_or(0)
+}
+
+/// A file whose latest change was a tag edit made by us.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) struct TagEdit {
+    /// Modification time before our first tag edit, i.e. of the last change
+    /// made by someone else
+    #[serde(rename = "contentModified")]
+    pub content_modified: u64,
+    /// Modification time our latest tag edit left behind
+    #[serde(rename = "tagsModified")]
+    pub tags_modified: u64,
+}
+
+/// Absolute path -> latest tag edit, so that a rescan can tell tag-only edits
+/// from files that are new or were replaced.
+pub(crate) type TagEditIndex = HashMap<String, TagEdit>;
+
+fn index_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join("tag-edits.json"))
+}
+
+pub(crate) fn load_tag_edits(app: &AppHandle) -> TagEditIndex {
+    index_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Record that we rewrote the tags of `edited` files, given as path and the
+/// modification time each had before the write. Only these files are checked:
+/// an entry that no longer matches its file is ignored by scans anyway.
+pub(crate) fn record_tag_edits(app: &AppHandle, edited: &[(String, u64)]) -> Result<(), String> {
+    if edited.is_empty() {
+        return Ok(());
+    }
+    let mut index = load_tag_edits(app);
+    for (path, before) in edited {
+        let after = modified_millis(Path::new(path));
+        if after == *before {
+            // The times were preserved, so nobody will see this write as a
+            // change; an entry left from an earlier edit still holds if the
+            // file kept the time that edit gave it
+            if index
+                .get(path)
+                .is_some_and(|edit| edit.tags_modified != after)
+            {
+                index.remove(path);
+            }
+            continue;
+        }
+        // A file edited again keeps the time of the last change that was not ours
+        let content_modified = match index.get(path) {
+            Some(edit) if edit.tags_modified == *before => edit.content_modified,
+            _ => *before,
+        };
+        index.insert(
+            path.clone(),
+            TagEdit {
+                content_modified,
+                tags_modified: after,
+            },
+        );
+    }
+    let path = index_path(app)?;
+    let json = serde_json::to_string(&index).map_err(|e| e.to_string())?;
+    // Write through a temporary file so that a crash never leaves half an index
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write tag edit index: {}", e))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write tag edit index: {}", e))
+}
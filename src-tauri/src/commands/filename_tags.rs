@@ -0,0 +1,193 @@
+use crate::commands::fs::{read_tags_for_edit, AudioTags, TagWriteResult};
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::write_options::TagWriteOptions;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FilenameTagPreview {
+    pub path: String,
+    /// Whether the pattern matched the file's path
+    pub matched: bool,
+    /// Current tags with the parsed fields applied (unchanged tags if not matched)
+    pub tags: AudioTags,
+}
+
+/// Fields that can appear in a pattern; numeric ones only match digits
+const TEXT_FIELDS: &[&str] = &[
+    "title",
+    "artist",
+    "albumartist",
+    "album",
+    "genre",
+    "composer",
+    "comment",
+];
+const NUMBER_FIELDS: &[&str] = &["year", "track", "totaltracks", "disc", "totaldiscs"];
+
+/// A user pattern such as `%artist%/%album%/%track% - %title%` compiled to a
+/// regex; `%%` stands for a literal `%`.
+struct FilenamePattern {
+    regex: Regex,
+    fields: Vec<String>,
+    /// Number of path components (directories + file name) the pattern covers
+    depth: usize,
+}
+
+impl FilenamePattern {
+    fn compile(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.replace('\\', "/");
+        let pattern = pattern.trim_matches('/');
+        if pattern.is_empty() {
+            return Err("Pattern must not be empty".to_string());
+        }
+
+        let mut source = String::from("^");
+        let mut fields = Vec::new();
+        let mut literal = String::new();
+        let mut rest = pattern;
+
+        // `%field%` is a field, `%%` a literal percent sign
+        while let Some(start) = rest.find('%') {
+            literal.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            if let Some(tail) = after.strip_prefix('%') {
+                literal.push('%');
+                rest = tail;
+                continue;
+            }
+            let end = after
+                .find('%')
+                .ok_or_else(|| format!("Unclosed field in pattern: %{}", after))?;
+            source.push_str(&regex::escape(&literal));
+            literal.clear();
+
+            let field = after[..end].to_lowercase();
+            if NUMBER_FIELDS.contains(&field.as_str()) {
+                source.push_str(r"(\d+)");
+                fields.push(field);
+            } else if TEXT_FIELDS.contains(&field.as_str()) {
+                source.push_str("(.+?)");
+                fields.push(field);
+            } else if field == "dummy" || field == "ignore" {
+                source.push_str(".+?");
+            } else {
+                return Err(format!("Unknown field in pattern: %{}%", field));
+            }
+            rest = &after[end + 1..];
+        }
+        literal.push_str(rest);
+        source.push_str(&regex::escape(&literal));
+        source.push('$');
+
+        Ok(FilenamePattern {
+            regex: Regex::new(&source).map_err(|e| format!("Invalid pattern: {}", e))?,
+            fields,
+            depth: pattern.split('/').count(),
+        })
+    }
+
+    /// Match the last `depth` components of `path` (without extension) against the pattern.
+    fn parse(&self, path: &Path) -> Option<Vec<(String, String)>> {
+        let stemmed = path.with_extension("");
+        let components: Vec<String> = stemmed
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if components.len() < self.depth {
+            return None;
+        }
+        let tail = components[components.len() - self.depth..].join("/");
+
+        let caps = self.regex.captures(&tail)?;
+        Some(
+            self.fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| (f.clone(), caps[i + 1].trim().to_string()))
+                .collect(),
+        )
+    }
+}
+
+fn apply_field(tags: &mut AudioTags, field: &str, value: String) {
+    let number = |v: &str| v.parse::<i64>().unwrap_or(0);
+    match field {
+        "title" => tags.title = value,
+        "artist" => tags.artist = value,
+        "albumartist" => tags.album_artist = value,
+        "album" => tags.album = value,
+        "genre" => tags.genre = value,
+        "composer" => tags.composer = value,
+        "comment" => tags.comment = value,
+        "year" => tags.year = number(&value),
+        "track" => tags.track_number = number(&value),
+        "totaltracks" => tags.total_tracks = number(&value),
+        "disc" => tags.disc_number = number(&value),
+        "totaldiscs" => tags.total_discs = number(&value),
+        _ => {}
+    }
+}
+
+fn preview_one(pattern: &FilenamePattern, path: &str) -> FilenameTagPreview {
+    let file_path = Path::new(path);
+    // Cover art is left empty so that writing the result keeps existing pictures
+    let mut tags = read_tags_for_edit(file_path, false);
+
+    let parsed = pattern.parse(file_path);
+    let matched = parsed.is_some();
+    for (field, value) in parsed.unwrap_or_default() {
+        apply_field(&mut tags, &field, value);
+    }
+
+    FilenameTagPreview {
+        path: path.to_string(),
+        matched,
+        tags,
+    }
+}
+
+/// Parse tag fields out of each file's path using a `%field%` pattern, without writing.
+#[tauri::command]
+pub async fn preview_tags_from_filename(
+    paths: Vec<String>,
+    pattern: String,
+) -> Result<Vec<FilenameTagPreview>, String> {
+    let pattern = FilenamePattern::compile(&pattern)?;
+    Ok(paths.iter().map(|p| preview_one(&pattern, p)).collect())
+}
+
+/// Parse tag fields out of each file's path and write them. Files whose path does
+/// not match the pattern are reported as failures and left untouched. `options`
+/// applies as in `write_audio_tags`.
+#[tauri::command]
+pub async fn apply_tags_from_filename(
+    app: AppHandle,
+    paths: Vec<String>,
+    pattern: String,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<TagWriteResult>, String> {
+    let pattern = FilenamePattern::compile(&pattern)?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let mut recorder =
+        TagEditRecorder::begin(&app, "Tags from file name")?.with_write_options(options);
+
+    let mut results: Vec<TagWriteResult> = paths
+        .iter()
+        .map(|path| {
+            let preview = preview_one(&pattern, path);
+            let result = if preview.matched {
+                recorder.write(Path::new(path), &preview.tags)
+            } else {
+                Err("Path does not match the pattern".to_string())
+            };
+            TagWriteResult::from_result(path, result)
+        })
+        .collect();
+
+    recorder.finish_with_results(&mut results);
+    Ok(results)
+}
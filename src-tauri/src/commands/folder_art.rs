@@ -0,0 +1,191 @@
+use crate::commands::artwork::{shrink_image, ArtworkOptions};
+use crate::commands::fs::{
+    picture_mime, read_front_cover, sniff_image_mime, TagWriteResult, MUSIC_EXTENSIONS,
+};
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::pictures::{file_extension, read_pictures};
+use crate::commands::write_options::TagWriteOptions;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Sidecar file names tried when the caller does not configure any, in priority order
+pub(crate) const DEFAULT_ARTWORK_PATTERNS: &[&str] =
+    &["cover.*", "folder.*", "front.*", "album.*", "albumart*.*"];
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "tif", "tiff", "webp"];
+
+/// Case-insensitive match of a file name against a pattern where `*` matches
+/// any run of characters.
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name = name.to_lowercase();
+    let pattern = pattern.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return name == pattern;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn resolve_patterns(patterns: Option<Vec<String>>) -> Vec<String> {
+    match patterns {
+        Some(p) if !p.is_empty() => p,
+        _ => DEFAULT_ARTWORK_PATTERNS
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    }
+}
+
+/// The first image in `dir` matching `patterns`; earlier patterns win, and
+/// within one pattern file names are compared alphabetically.
+pub(crate) fn find_folder_art(dir: &Path, patterns: &[String]) -> Option<PathBuf> {
+    let mut images: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .filter_map(|p| {
+            let name = p.file_name()?.to_string_lossy().to_string();
+            Some((name, p))
+        })
+        .collect();
+    images.sort();
+
+    patterns.iter().find_map(|pattern| {
+        images
+            .iter()
+            .find(|(name, _)| matches_pattern(name, pattern))
+            .map(|(_, path)| path.clone())
+    })
+}
+
+/// An image file as `(mime type, bytes)`, `None` if it is not a supported image.
+pub(crate) fn read_image(path: &Path) -> Option<(String, Vec<u8>)> {
+    let bytes = fs::read(path).ok()?;
+    let mime = sniff_image_mime(&bytes)?;
+    Some((mime.as_str().to_string(), bytes))
+}
+
+/// Folder artwork next to `track` as `(mime type, bytes)`.
+pub(crate) fn read_folder_art(track: &Path, patterns: &[String]) -> Option<(String, Vec<u8>)> {
+    read_image(&find_folder_art(track.parent()?, patterns)?)
+}
+
+/// Folder artwork next to `track` as a data URI, for `read_audio_tags`.
+pub(crate) fn folder_art_data_uri(track: &Path, patterns: Option<Vec<String>>) -> Option<String> {
+    use base64::Engine;
+
+    let (mime, bytes) = read_folder_art(track, &resolve_patterns(patterns))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    Some(format!("data:{};base64,{}", mime, encoded))
+}
+
+/// Write the track's embedded front cover (or its first picture) next to it.
+/// `file_name` defaults to `cover.<ext>`; returns the written path.
+#[tauri::command]
+pub async fn export_cover_to_folder(
+    path: String,
+    file_name: Option<String>,
+    overwrite: bool,
+) -> Result<String, String> {
+    let file_path = Path::new(&path);
+    let (mime, bytes) = match read_front_cover(file_path) {
+        Some(cover) => cover,
+        None => read_pictures(file_path)?
+            .first()
+            .map(|pic| (picture_mime(pic), pic.data().to_vec()))
+            .ok_or("The track has no embedded artwork")?,
+    };
+
+    let dir = file_path.parent().ok_or("Track has no parent directory")?;
+    let name = file_name.unwrap_or_else(|| format!("cover.{}", file_extension(&mime)));
+    if name.contains(['/', '\\']) {
+        return Err(format!("Invalid file name: {}", name));
+    }
+    let target = dir.join(name);
+    if target.exists() && !overwrite {
+        return Err(format!("File already exists: {}", target.display()));
+    }
+
+    fs::write(&target, bytes).map_err(|e| format!("Failed to write image: {}", e))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Embed the folder artwork of `directory` as the front cover of every track
+/// directly in it. Tracks that already have a front cover are skipped unless
+/// `overwrite` is set. `options` applies as in `write_audio_tags`.
+#[tauri::command]
+pub async fn embed_folder_art(
+    app: AppHandle,
+    directory: String,
+    patterns: Option<Vec<String>>,
+    extensions: Option<Vec<String>>,
+    overwrite: bool,
+    artwork: Option<ArtworkOptions>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<TagWriteResult>, String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let dir = Path::new(&directory);
+    let image = find_folder_art(dir, &resolve_patterns(patterns))
+        .ok_or_else(|| format!("No folder artwork found in {}", directory))?;
+    let mut cover =
+        read_image(&image).ok_or_else(|| format!("Not a supported image: {}", image.display()))?;
+    if let Some(options) = &artwork {
+        if let Some(bytes) = shrink_image(&cover.1, options)? {
+            cover = ("image/jpeg".to_string(), bytes);
+        }
+    }
+
+    let extensions: Vec<String> = extensions
+        .unwrap_or_else(|| MUSIC_EXTENSIONS.iter().map(|s| s.to_string()).collect())
+        .iter()
+        .map(|e| e.to_lowercase())
+        .collect();
+    let mut tracks: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.contains(&e.to_lowercase()))
+        })
+        .collect();
+    tracks.sort();
+
+    let mut recorder =
+        TagEditRecorder::begin(&app, "Embed folder artwork")?.with_write_options(options);
+    let mut results = Vec::with_capacity(tracks.len());
+    for track in tracks {
+        if !overwrite && read_front_cover(&track).is_some() {
+            continue;
+        }
+        let result = recorder.set_front_cover(&track, Some(cover.clone()));
+        results.push(TagWriteResult::from_result(
+            &track.to_string_lossy(),
+            result,
+        ));
+    }
+    recorder.finish_with_results(&mut results);
+    Ok(results)
+}
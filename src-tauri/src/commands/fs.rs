@@ -1,10 +1,21 @@
+use crate::commands::artwork::{shrink_data_uri, ArtworkOptions};
+use crate::commands::audio_properties::{read_properties, AudioProperties};
+use crate::commands::custom_fields::{self, CustomFields};
+use crate::commands::file_times::{load_tag_edits, modified_millis, TagEditIndex};
+use crate::commands::folder_art::folder_art_data_uri;
+use crate::commands::id3v2;
+use crate::commands::journal::{FileResult, TagEditRecorder};
+use crate::commands::lyrics::{self, LyricLine};
+use crate::commands::pictures::PictureSelection;
+use crate::commands::ratings::{self, PopularityUpdate};
+use crate::commands::write_options::TagWriteOptions;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tauri::AppHandle;
 use tauri_plugin_dialog::DialogExt;
 use walkdir::WalkDir;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Track {
     pub title: String,
     pub artist: String,
@@ -30,14 +41,110 @@ pub struct Track {
     pub isrc: String,
     #[serde(rename = "coverArt")]
     pub cover_art: String,
+    /// All artist values; `artist` holds them joined for display
+    #[serde(default)]
+    pub artists: Vec<String>,
+    #[serde(rename = "albumArtists", default)]
+    pub album_artists: Vec<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub composers: Vec<String>,
+    /// ReplayGain values as stored in the tag, e.g. "-6.54 dB" / "0.988547";
+    /// empty when the file has none
+    #[serde(rename = "replayGainTrackGain", default)]
+    pub replay_gain_track_gain: String,
+    #[serde(rename = "replayGainTrackPeak", default)]
+    pub replay_gain_track_peak: String,
+    #[serde(rename = "replayGainAlbumGain", default)]
+    pub replay_gain_album_gain: String,
+    #[serde(rename = "replayGainAlbumPeak", default)]
+    pub replay_gain_album_peak: String,
+    /// MusicBrainz recording id (Picard's "MusicBrainz Track Id")
+    #[serde(rename = "musicBrainzTrackId", default)]
+    pub musicbrainz_track_id: String,
+    #[serde(rename = "musicBrainzReleaseId", default)]
+    pub musicbrainz_release_id: String,
+    #[serde(rename = "musicBrainzArtistId", default)]
+    pub musicbrainz_artist_id: String,
+    #[serde(rename = "musicBrainzReleaseGroupId", default)]
+    pub musicbrainz_release_group_id: String,
+    /// The extended fields below are empty / 0 when the file does not have them
+    #[serde(rename = "originalYear", default)]
+    pub original_year: i64,
+    /// Full release date as stored, e.g. "2001-03-07"
+    #[serde(rename = "releaseDate", default)]
+    pub release_date: String,
+    #[serde(rename = "catalogNumber", default)]
+    pub catalog_number: String,
+    #[serde(default)]
+    pub barcode: String,
+    /// Record label; read from the same field as `publisher` (TPUB / LABEL)
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub conductor: String,
+    #[serde(default)]
+    pub lyricist: String,
+    #[serde(default)]
+    pub remixer: String,
+    #[serde(default)]
+    pub grouping: String,
+    #[serde(rename = "artistSort", default)]
+    pub artist_sort: String,
+    #[serde(rename = "albumSort", default)]
+    pub album_sort: String,
+    #[serde(rename = "titleSort", default)]
+    pub title_sort: String,
+    #[serde(rename = "mediaType", default)]
+    pub media_type: String,
+    /// 0-100 (20 per star), normalized across players; 0 = unrated
+    #[serde(default)]
+    pub rating: i64,
+    #[serde(rename = "playCount", default)]
+    pub play_count: i64,
+    #[serde(default)]
+    pub loved: bool,
     pub duration: i64,
     #[serde(rename = "relativePath")]
     pub relative_path: String,
     #[serde(rename = "absolutePath")]
     pub absolute_path: String,
+    /// Last change to the file in ms since the epoch, not counting our own tag
+    /// edits, so that retagging does not make a file look newly added
+    #[serde(rename = "modifiedAt", default)]
+    pub modified_at: u64,
+    /// Time of our latest tag edit if that is the file's latest change, else 0
+    #[serde(rename = "tagsModifiedAt", default)]
+    pub tags_modified_at: u64,
+    /// Audio bitrate in kbps; this and the other technical properties are 0
+    /// / empty when unknown
+    #[serde(default)]
+    pub bitrate: i64,
+    /// Hz
+    #[serde(rename = "sampleRate", default)]
+    pub sample_rate: i64,
+    /// Bits per sample of lossless files; 0 for lossy codecs
+    #[serde(rename = "bitDepth", default)]
+    pub bit_depth: i64,
+    #[serde(default)]
+    pub channels: i64,
+    /// "MP3" | "AAC" | "ALAC" | "FLAC" | "Opus" | "Vorbis" | "PCM" | ...
+    #[serde(default)]
+    pub codec: String,
+    /// "MPEG" | "MP4" | "FLAC" | "Ogg" | "WAV" | "AIFF" | ...
+    #[serde(default)]
+    pub container: String,
+    /// Bytes
+    #[serde(rename = "fileSize", default)]
+    pub file_size: u64,
+    #[serde(default)]
+    pub lossless: bool,
+    #[serde(rename = "durationMs", default)]
+    pub duration_ms: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct AudioTags {
     pub title: String,
     pub artist: String,
@@ -63,9 +170,98 @@ pub struct AudioTags {
     pub isrc: String,
     #[serde(rename = "coverArt")]
     pub cover_art: String,
+    /// All artist values. When it no longer matches `artist` (e.g. the display
+    /// string was edited), `artist` wins and is split on `;` instead.
+    #[serde(default)]
+    pub artists: Vec<String>,
+    #[serde(rename = "albumArtists", default)]
+    pub album_artists: Vec<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub composers: Vec<String>,
+    /// ReplayGain values as stored in the tag, e.g. "-6.54 dB" / "0.988547".
+    /// `None` leaves the file's value untouched, an empty string removes it;
+    /// the same holds for the MusicBrainz ids.
+    #[serde(rename = "replayGainTrackGain", default)]
+    pub replay_gain_track_gain: Option<String>,
+    #[serde(rename = "replayGainTrackPeak", default)]
+    pub replay_gain_track_peak: Option<String>,
+    #[serde(rename = "replayGainAlbumGain", default)]
+    pub replay_gain_album_gain: Option<String>,
+    #[serde(rename = "replayGainAlbumPeak", default)]
+    pub replay_gain_album_peak: Option<String>,
+    /// MusicBrainz recording id (Picard's "MusicBrainz Track Id")
+    #[serde(rename = "musicBrainzTrackId", default)]
+    pub musicbrainz_track_id: Option<String>,
+    #[serde(rename = "musicBrainzReleaseId", default)]
+    pub musicbrainz_release_id: Option<String>,
+    #[serde(rename = "musicBrainzArtistId", default)]
+    pub musicbrainz_artist_id: Option<String>,
+    #[serde(rename = "musicBrainzReleaseGroupId", default)]
+    pub musicbrainz_release_group_id: Option<String>,
+    /// The extended fields below are kept as they are in the file when `None`
+    /// and removed when empty / 0
+    #[serde(rename = "originalYear", default)]
+    pub original_year: Option<i64>,
+    /// Full release date as stored, e.g. "2001-03-07". Its year must agree with
+    /// a non-zero `year`
+    #[serde(rename = "releaseDate", default)]
+    pub release_date: Option<String>,
+    #[serde(rename = "catalogNumber", default)]
+    pub catalog_number: Option<String>,
+    #[serde(default)]
+    pub barcode: Option<String>,
+    /// Record label. Stored in the same field as `publisher` (TPUB / LABEL);
+    /// when the two disagree, the one that differs from the file wins
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub conductor: Option<String>,
+    #[serde(default)]
+    pub lyricist: Option<String>,
+    #[serde(default)]
+    pub remixer: Option<String>,
+    #[serde(default)]
+    pub grouping: Option<String>,
+    #[serde(rename = "artistSort", default)]
+    pub artist_sort: Option<String>,
+    #[serde(rename = "albumSort", default)]
+    pub album_sort: Option<String>,
+    #[serde(rename = "titleSort", default)]
+    pub title_sort: Option<String>,
+    #[serde(rename = "mediaType", default)]
+    pub media_type: Option<String>,
+    /// 0-100 (20 per star), normalized across players; 0 = unrated. This and
+    /// the play count and loved flag are left as they are when `None`
+    #[serde(default)]
+    pub rating: Option<i64>,
+    #[serde(rename = "playCount", default)]
+    pub play_count: Option<i64>,
+    #[serde(default)]
+    pub loved: Option<bool>,
+    /// Fields outside this model (TXXX / Vorbis / freeform). `None` leaves the
+    /// file's custom fields untouched; `Some` makes them exactly this map.
+    #[serde(rename = "customFields", default)]
+    pub custom_fields: Option<CustomFields>,
+    /// Time-synced lyrics (ID3 SYLT, or LRC text in the lyrics field). `None`
+    /// leaves them untouched; an empty list removes them.
+    #[serde(rename = "syncedLyrics", default)]
+    pub synced_lyrics: Option<Vec<LyricLine>>,
+    /// Picture types to remove before `cover_art` is applied ("coverFront",
+    /// "coverBack", ... or "all"). `None` leaves existing pictures alone.
+    #[serde(rename = "removePictures", default)]
+    pub remove_pictures: Option<Vec<String>>,
+    /// Where `cover_art` came from when read: "embedded", "sidecar" (folder
+    /// artwork such as cover.jpg) or empty. Ignored when writing.
+    #[serde(rename = "coverArtSource", default)]
+    pub cover_art_source: String,
 }
 
-const MUSIC_EXTENSIONS: &[&str] = &["flac", "mp3", "aac", "wav", "m4a"];
+/// Separator used to show multi-value fields as a single string
+pub const MULTI_VALUE_SEPARATOR: &str = "; ";
+
+pub(crate) const MUSIC_EXTENSIONS: &[&str] = &["flac", "mp3", "aac", "wav", "m4a"];
 
 #[tauri::command]
 pub async fn select_music_root(app: AppHandle) -> Result<Option<String>, String> {
@@ -83,6 +279,7 @@ pub async fn select_directory(app: AppHandle) -> Result<Option<String>, String>
 
 #[tauri::command]
 pub async fn scan_music_directory(
+    app: AppHandle,
     path: String,
     extensions: Option<Vec<String>>,
     exclude_patterns: Option<Vec<String>>,
@@ -95,6 +292,7 @@ pub async fn scan_music_directory(
     let allowed_exts: Vec<String> =
         extensions.unwrap_or_else(|| MUSIC_EXTENSIONS.iter().map(|s| s.to_string()).collect());
     let exclude = exclude_patterns.unwrap_or_default();
+    let tag_edits = load_tag_edits(&app);
 
     let mut tracks = Vec::new();
 
@@ -127,7 +325,7 @@ pub async fn scan_music_directory(
 
         if let Some(ext) = ext {
             if allowed_exts.iter().any(|e| e.eq_ignore_ascii_case(&ext)) {
-                let track = build_track(file_path, root);
+                let track = build_track(file_path, root, &tag_edits);
                 tracks.push(track);
             }
         }
@@ -139,7 +337,7 @@ pub async fn scan_music_directory(
     Ok(tracks)
 }
 
-fn build_track(file_path: &Path, root: &Path) -> Track {
+fn build_track(file_path: &Path, root: &Path, tag_edits: &TagEditIndex) -> Track {
     let absolute_path = file_path.to_string_lossy().to_string();
 
     // relative path from music root
@@ -152,31 +350,18 @@ fn build_track(file_path: &Path, root: &Path) -> Track {
     // Try to read metadata (cover art excluded during scan for performance)
     let meta = read_audio_metadata(file_path, false);
 
-    Track {
-        title: meta.title,
-        artist: meta.artist,
-        album_artist: meta.album_artist,
-        album: meta.album,
-        genre: meta.genre,
-        year: meta.year,
-        track_number: meta.track_number,
-        total_tracks: meta.total_tracks,
-        disc_number: meta.disc_number,
-        total_discs: meta.total_discs,
-        composer: meta.composer,
-        comment: meta.comment,
-        lyrics: meta.lyrics,
-        bpm: meta.bpm,
-        copyright: meta.copyright,
-        publisher: meta.publisher,
-        isrc: meta.isrc,
-        cover_art: meta.cover_art,
-        duration: meta.duration,
-        relative_path,
-        absolute_path,
-    }
+    let modified = modified_millis(file_path);
+    let tag_edit = tag_edits
+        .get(&absolute_path)
+        .filter(|edit| edit.tags_modified == modified);
+
+    let mut track = Track::from_metadata(meta, relative_path, absolute_path);
+    track.modified_at = tag_edit.map_or(modified, |edit| edit.content_modified);
+    track.tags_modified_at = tag_edit.map_or(0, |edit| edit.tags_modified);
+    track
 }
 
+#[derive(Default)]
 pub struct AudioMetadata {
     pub title: String,
     pub artist: String,
@@ -196,252 +381,668 @@ pub struct AudioMetadata {
     pub publisher: String,
     pub isrc: String,
     pub cover_art: String,
+    pub artists: Vec<String>,
+    pub album_artists: Vec<String>,
+    pub genres: Vec<String>,
+    pub composers: Vec<String>,
+    pub replay_gain_track_gain: String,
+    pub replay_gain_track_peak: String,
+    pub replay_gain_album_gain: String,
+    pub replay_gain_album_peak: String,
+    /// MusicBrainz recording id (Picard's "MusicBrainz Track Id")
+    pub musicbrainz_track_id: String,
+    pub musicbrainz_release_id: String,
+    pub musicbrainz_artist_id: String,
+    pub musicbrainz_release_group_id: String,
+    pub original_year: i64,
+    /// Full release date as stored, e.g. "2001-03-07"; `year` holds its year
+    pub release_date: String,
+    pub catalog_number: String,
+    pub barcode: String,
+    pub label: String,
+    pub conductor: String,
+    pub lyricist: String,
+    pub remixer: String,
+    pub grouping: String,
+    pub artist_sort: String,
+    pub album_sort: String,
+    pub title_sort: String,
+    pub media_type: String,
+    pub rating: i64,
+    pub play_count: i64,
+    pub loved: bool,
     pub duration: i64,
+    pub properties: AudioProperties,
+}
+
+impl Track {
+    pub(crate) fn from_metadata(
+        meta: AudioMetadata,
+        relative_path: String,
+        absolute_path: String,
+    ) -> Self {
+        Track {
+            title: meta.title,
+            artist: meta.artist,
+            album_artist: meta.album_artist,
+            album: meta.album,
+            genre: meta.genre,
+            year: meta.year,
+            track_number: meta.track_number,
+            total_tracks: meta.total_tracks,
+            disc_number: meta.disc_number,
+            total_discs: meta.total_discs,
+            composer: meta.composer,
+            comment: meta.comment,
+            lyrics: meta.lyrics,
+            bpm: meta.bpm,
+            copyright: meta.copyright,
+            publisher: meta.publisher,
+            isrc: meta.isrc,
+            cover_art: meta.cover_art,
+            artists: meta.artists,
+            album_artists: meta.album_artists,
+            genres: meta.genres,
+            composers: meta.composers,
+            replay_gain_track_gain: meta.replay_gain_track_gain,
+            replay_gain_track_peak: meta.replay_gain_track_peak,
+            replay_gain_album_gain: meta.replay_gain_album_gain,
+            replay_gain_album_peak: meta.replay_gain_album_peak,
+            musicbrainz_track_id: meta.musicbrainz_track_id,
+            musicbrainz_release_id: meta.musicbrainz_release_id,
+            musicbrainz_artist_id: meta.musicbrainz_artist_id,
+            musicbrainz_release_group_id: meta.musicbrainz_release_group_id,
+            original_year: meta.original_year,
+            release_date: meta.release_date,
+            catalog_number: meta.catalog_number,
+            barcode: meta.barcode,
+            label: meta.label,
+            conductor: meta.conductor,
+            lyricist: meta.lyricist,
+            remixer: meta.remixer,
+            grouping: meta.grouping,
+            artist_sort: meta.artist_sort,
+            album_sort: meta.album_sort,
+            title_sort: meta.title_sort,
+            media_type: meta.media_type,
+            rating: meta.rating,
+            play_count: meta.play_count,
+            loved: meta.loved,
+            duration: meta.duration,
+            relative_path,
+            absolute_path,
+            modified_at: 0,
+            tags_modified_at: 0,
+            bitrate: meta.properties.bitrate,
+            sample_rate: meta.properties.sample_rate,
+            bit_depth: meta.properties.bit_depth,
+            channels: meta.properties.channels,
+            codec: meta.properties.codec,
+            container: meta.properties.container,
+            file_size: meta.properties.file_size,
+            lossless: meta.properties.lossless,
+            duration_ms: meta.properties.duration_ms,
+        }
+    }
+}
+
+/// Prefix of iTunes-style freeform atoms in MP4 files
+pub(crate) const MP4_FREEFORM_PREFIX: &str = "----:com.apple.iTunes:";
+
+/// The key to use for `key` in a tag of `tag_type`. For MP4, keys lofty has no
+/// atom mapping for are stored as iTunes freeform atoms named `atom`.
+pub(crate) fn mp4_freeform_key(
+    tag_type: lofty::tag::TagType,
+    key: lofty::tag::ItemKey,
+    atom: &str,
+) -> lofty::tag::ItemKey {
+    if tag_type == lofty::tag::TagType::Mp4Ilst && key.map_key(tag_type, false).is_none() {
+        lofty::tag::ItemKey::Unknown(format!("{}{}", MP4_FREEFORM_PREFIX, atom))
+    } else {
+        key
+    }
+}
+
+/// Normalize a ReplayGain gain to the conventional "-6.54 dB" form.
+fn format_replay_gain(value: &str) -> String {
+    let number = value.trim().trim_end_matches("dB").trim();
+    match number.parse::<f64>() {
+        Ok(gain) => format!("{:.2} dB", gain),
+        Err(_) => value.trim().to_string(),
+    }
 }
 
+/// Normalize a ReplayGain peak to six decimals.
+fn format_replay_peak(value: &str) -> String {
+    match value.trim().parse::<f64>() {
+        Ok(peak) => format!("{:.6}", peak),
+        Err(_) => value.trim().to_string(),
+    }
+}
+
+/// Year of a date such as "1999", "1999-04" or "1999-04-12"; 0 if there is none.
+fn parse_year(date: &str) -> i64 {
+    date.trim()
+        .get(..4)
+        .and_then(|y| y.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Metadata for display: an untitled file is shown under its file name.
 pub fn read_audio_metadata(path: &Path, include_cover: bool) -> AudioMetadata {
-    use lofty::prelude::*;
-    use lofty::probe::Probe;
+    let mut meta = read_stored_metadata(path, include_cover);
+    if meta.title.is_empty() {
+        meta.title = default_title(path);
+    }
+    meta
+}
 
-    let default_title = path
-        .file_stem()
+fn default_title(path: &Path) -> String {
+    path.file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("Unknown")
-        .to_string();
+        .to_string()
+}
+
+/// Metadata as stored in the file, without display fallbacks, so that writing
+/// it back changes nothing. Read-modify-write paths must use this rather than
+/// `read_audio_metadata`.
+pub(crate) fn read_stored_metadata(path: &Path, include_cover: bool) -> AudioMetadata {
+    use lofty::prelude::*;
+    use lofty::probe::Probe;
+    use lofty::tag::{ItemKey, TagType};
+
+    let mut meta = AudioMetadata::default();
 
     let tagged = match Probe::open(path).and_then(|p| p.read()) {
         Ok(t) => t,
-        Err(_) => {
-            return AudioMetadata {
-                title: default_title,
-                artist: String::new(),
-                album_artist: String::new(),
-                album: String::new(),
-                genre: String::new(),
-                year: 0,
-                track_number: 0,
-                total_tracks: 0,
-                disc_number: 0,
-                total_discs: 0,
-                composer: String::new(),
-                comment: String::new(),
-                lyrics: String::new(),
-                bpm: 0,
-                copyright: String::new(),
-                publisher: String::new(),
-                isrc: String::new(),
-                cover_art: String::new(),
-                duration: 0,
-            }
-        }
+        Err(_) => return meta,
     };
 
-    let duration = tagged.properties().duration().as_secs() as i64;
-
-    let tag = tagged.primary_tag().or_else(|| tagged.first_tag());
-
-    let (
-        title,
-        artist,
-        album_artist,
-        album,
-        genre,
-        year,
-        track_number,
-        total_tracks,
-        disc_number,
-        total_discs,
-        composer,
-        comment,
-        lyrics,
-        bpm,
-        copyright,
-        publisher,
-        isrc,
-        cover_art,
-    ) = if let Some(tag) = tag {
-        use lofty::prelude::Accessor;
-        use lofty::tag::ItemKey;
-
-        let t = tag
-            .title()
-            .map(|s| s.to_string())
-            .unwrap_or(default_title.clone());
-        let a = tag.artist().map(|s| s.to_string()).unwrap_or_default();
-        let aa = tag
-            .get_string(&ItemKey::AlbumArtist)
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        let al = tag.album().map(|s| s.to_string()).unwrap_or_default();
-        let g = tag.genre().map(|s| s.to_string()).unwrap_or_default();
-        let y = tag.year().unwrap_or(0) as i64;
-        let tn = tag.track().unwrap_or(0) as i64;
-        let tt = tag.track_total().unwrap_or(0) as i64;
-        let dn = tag.disk().unwrap_or(0) as i64;
-        let dt = tag.disk_total().unwrap_or(0) as i64;
-        let comp = tag
-            .get_string(&ItemKey::Composer)
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        let comm = tag
-            .get_string(&ItemKey::Comment)
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        let lyr = tag
-            .get_string(&ItemKey::Lyrics)
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        let b: i64 = tag
-            .get_string(&ItemKey::Bpm)
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0);
-        let copy = tag
-            .get_string(&ItemKey::CopyrightMessage)
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        let pub_ = tag
-            .get_string(&ItemKey::Label)
+    meta.duration = tagged.properties().duration().as_secs() as i64;
+    meta.properties = read_properties(path, &tagged);
+
+    let Some(tag) = tagged.primary_tag().or_else(|| tagged.first_tag()) else {
+        return meta;
+    };
+
+    let text = |key: ItemKey| {
+        tag.get_string(&key)
             .map(|s| s.to_string())
-            .unwrap_or_default();
-        let isrc_ = tag
-            .get_string(&ItemKey::Isrc)
+            .unwrap_or_default()
+    };
+    // Every value of a multi-value field (Vorbis comments repeat the key,
+    // ID3v2.4 separates values with NUL, MP4 stores several data atoms)
+    let values = |key: ItemKey| {
+        tag.get_strings(&key)
             .map(|s| s.to_string())
-            .unwrap_or_default();
+            .collect::<Vec<_>>()
+    };
 
-        let cover = if include_cover {
-            extract_cover_from_tag(tag)
+    if let Some(title) = tag.title() {
+        meta.title = title.to_string();
+    }
+    meta.artists = values(ItemKey::TrackArtist);
+    meta.artist = meta.artists.join(MULTI_VALUE_SEPARATOR);
+    meta.album_artists = values(ItemKey::AlbumArtist);
+    meta.album_artist = meta.album_artists.join(MULTI_VALUE_SEPARATOR);
+    meta.album = tag.album().map(|s| s.to_string()).unwrap_or_default();
+    meta.genres = values(ItemKey::Genre);
+    meta.genre = meta.genres.join(MULTI_VALUE_SEPARATOR);
+    meta.year = tag.year().unwrap_or(0) as i64;
+    meta.track_number = tag.track().unwrap_or(0) as i64;
+    meta.total_tracks = tag.track_total().unwrap_or(0) as i64;
+    meta.disc_number = tag.disk().unwrap_or(0) as i64;
+    meta.total_discs = tag.disk_total().unwrap_or(0) as i64;
+    meta.composers = values(ItemKey::Composer);
+    meta.composer = meta.composers.join(MULTI_VALUE_SEPARATOR);
+    meta.comment = text(ItemKey::Comment);
+    meta.lyrics = text(ItemKey::Lyrics);
+    meta.bpm = text(ItemKey::Bpm).parse().unwrap_or(0);
+    meta.copyright = text(ItemKey::CopyrightMessage);
+    meta.isrc = text(ItemKey::Isrc);
+
+    let tag_type = tag.tag_type();
+    let replay_gain = |key: ItemKey, atom: &str| {
+        let value = text(mp4_freeform_key(tag_type, key, atom));
+        // Taggers disagree on the case of MP4 freeform atom names
+        if value.is_empty() && tag_type == TagType::Mp4Ilst {
+            text(ItemKey::Unknown(format!(
+                "{}{}",
+                MP4_FREEFORM_PREFIX,
+                atom.to_uppercase()
+            )))
         } else {
-            String::new()
-        };
+            value
+        }
+    };
+    meta.replay_gain_track_gain =
+        replay_gain(ItemKey::ReplayGainTrackGain, "replaygain_track_gain");
+    meta.replay_gain_track_peak =
+        replay_gain(ItemKey::ReplayGainTrackPeak, "replaygain_track_peak");
+    meta.replay_gain_album_gain =
+        replay_gain(ItemKey::ReplayGainAlbumGain, "replaygain_album_gain");
+    meta.replay_gain_album_peak =
+        replay_gain(ItemKey::ReplayGainAlbumPeak, "replaygain_album_peak");
 
-        (
-            t, a, aa, al, g, y, tn, tt, dn, dt, comp, comm, lyr, b, copy, pub_, isrc_, cover,
-        )
+    let extended = |key: ItemKey, atom: &str| text(mp4_freeform_key(tag_type, key, atom));
+    meta.musicbrainz_track_id = extended(ItemKey::MusicBrainzRecordingId, "MusicBrainz Track Id");
+    meta.musicbrainz_release_id = extended(ItemKey::MusicBrainzReleaseId, "MusicBrainz Album Id");
+    meta.musicbrainz_artist_id = extended(ItemKey::MusicBrainzArtistId, "MusicBrainz Artist Id");
+    meta.musicbrainz_release_group_id = extended(
+        ItemKey::MusicBrainzReleaseGroupId,
+        "MusicBrainz Release Group Id",
+    );
+    meta.original_year = parse_year(&extended(ItemKey::OriginalReleaseDate, "ORIGINALDATE"));
+    meta.release_date = text(ItemKey::RecordingDate);
+    meta.catalog_number = extended(ItemKey::CatalogNumber, "CATALOGNUMBER");
+    meta.barcode = extended(ItemKey::Barcode, "BARCODE");
+    // TPUB / LABEL holds the publisher as well as the record label
+    meta.publisher = extended(ItemKey::Label, "LABEL");
+    meta.label = meta.publisher.clone();
+    meta.conductor = extended(ItemKey::Conductor, "CONDUCTOR");
+    meta.lyricist = extended(ItemKey::Lyricist, "LYRICIST");
+    meta.remixer = extended(ItemKey::Remixer, "REMIXER");
+    meta.grouping = text(ItemKey::ContentGroup);
+    meta.artist_sort = text(ItemKey::TrackArtistSortOrder);
+    meta.album_sort = text(ItemKey::AlbumTitleSortOrder);
+    meta.title_sort = text(ItemKey::TrackTitleSortOrder);
+    meta.media_type = extended(ItemKey::OriginalMediaType, "MEDIA");
+
+    // POPM / PCNT are not part of the generic tag, so ID3v2 needs its own pass
+    let popularity = if id3v2::uses_id3v2(tagged.file_type()) {
+        id3v2::read_tag(path, tagged.file_type())
+            .ok()
+            .flatten()
+            .map(|id3| ratings::from_id3v2(&id3))
+            .unwrap_or_default()
     } else {
-        (
-            default_title,
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            0i64,
-            0i64,
-            0i64,
-            0i64,
-            0i64,
-            String::new(),
-            String::new(),
-            String::new(),
-            0i64,
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-        )
+        ratings::from_tag(tag)
     };
+    meta.rating = popularity.rating;
+    meta.play_count = popularity.play_count;
+    meta.loved = popularity.loved;
 
-    AudioMetadata {
-        title,
-        artist,
-        album_artist,
-        album,
-        genre,
-        year,
-        track_number,
-        total_tracks,
-        disc_number,
-        total_discs,
-        composer,
-        comment,
-        lyrics,
-        bpm,
-        copyright,
-        publisher,
-        isrc,
-        cover_art,
-        duration,
+    if include_cover {
+        meta.cover_art = extract_cover_from_tag(tag);
     }
+
+    meta
 }
 
 fn extract_cover_from_tag(tag: &lofty::tag::Tag) -> String {
-    if let Some(pic) = tag.pictures().first() {
-        use base64::Engine;
-        let mime = match pic.mime_type() {
-            Some(lofty::picture::MimeType::Jpeg) => "image/jpeg",
-            Some(lofty::picture::MimeType::Png) => "image/png",
-            Some(lofty::picture::MimeType::Bmp) => "image/bmp",
-            Some(lofty::picture::MimeType::Gif) => "image/gif",
-            Some(lofty::picture::MimeType::Tiff) => "image/tiff",
-            _ => "image/jpeg",
-        };
-        let encoded = base64::engine::general_purpose::STANDARD.encode(pic.data());
-        format!("data:{};base64,{}", mime, encoded)
-    } else {
-        String::new()
+    // The front cover if there is one, otherwise whatever picture comes first
+    let pictures = tag.pictures();
+    let front = pictures
+        .iter()
+        .find(|p| p.pic_type() == lofty::picture::PictureType::CoverFront);
+    match front.or_else(|| pictures.first()) {
+        Some(pic) => picture_data_uri(pic),
+        None => String::new(),
+    }
+}
+
+pub(crate) fn picture_data_uri(pic: &lofty::picture::Picture) -> String {
+    use base64::Engine;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(pic.data());
+    format!("data:{};base64,{}", picture_mime(pic), encoded)
+}
+
+/// MIME type of a picture, from its bytes rather than the (often wrong) declared type.
+pub(crate) fn picture_mime(pic: &lofty::picture::Picture) -> String {
+    match sniff_image_mime(pic.data()).or_else(|| pic.mime_type().cloned()) {
+        Some(mime) => mime.as_str().to_string(),
+        None => "application/octet-stream".to_string(),
     }
 }
 
 #[tauri::command]
-pub async fn read_audio_tags(path: String) -> Result<AudioTags, String> {
+pub async fn read_audio_tags(
+    path: String,
+    artwork_patterns: Option<Vec<String>>,
+) -> Result<AudioTags, String> {
     let file_path = Path::new(&path);
     if !file_path.exists() {
         return Err(format!("File not found: {}", path));
     }
 
-    let meta = read_audio_metadata(file_path, true);
-
-    Ok(AudioTags {
-        title: meta.title,
-        artist: meta.artist,
-        album_artist: meta.album_artist,
-        album: meta.album,
-        genre: meta.genre,
-        year: meta.year,
-        track_number: meta.track_number,
-        total_tracks: meta.total_tracks,
-        disc_number: meta.disc_number,
-        total_discs: meta.total_discs,
-        composer: meta.composer,
-        comment: meta.comment,
-        lyrics: meta.lyrics,
-        bpm: meta.bpm,
-        copyright: meta.copyright,
-        publisher: meta.publisher,
-        isrc: meta.isrc,
-        cover_art: meta.cover_art,
-    })
+    let mut tags = read_tags_for_edit(file_path, true);
+    if tags.title.is_empty() {
+        tags.title = default_title(file_path);
+    }
+    if !tags.cover_art.is_empty() {
+        tags.cover_art_source = "embedded".to_string();
+    } else if let Some(uri) = folder_art_data_uri(file_path, artwork_patterns) {
+        tags.cover_art = uri;
+        tags.cover_art_source = "sidecar".to_string();
+    }
+    Ok(tags)
+}
+
+/// Current tags of the file as stored, including the fields that scans skip
+/// (custom fields and synced lyrics), so that writing them back changes nothing.
+pub(crate) fn read_tags_for_edit(path: &Path, include_cover: bool) -> AudioTags {
+    use lofty::probe::Probe;
+
+    let mut tags = AudioTags::from(read_stored_metadata(path, include_cover));
+    let file_type = Probe::open(path)
+        .and_then(|p| p.guess_file_type())
+        .ok()
+        .and_then(|p| p.file_type());
+
+    match file_type {
+        Some(file_type) if id3v2::uses_id3v2(file_type) => {
+            if let Ok(id3) = id3v2::read_tag(path, file_type) {
+                let id3 = id3.as_ref();
+                tags.custom_fields = Some(id3.map(custom_fields::from_id3v2).unwrap_or_default());
+                tags.synced_lyrics = Some(id3.map(lyrics::from_id3v2).unwrap_or_default());
+            }
+        }
+        _ => {
+            tags.custom_fields = custom_fields::read_custom_fields(path).ok();
+            tags.synced_lyrics = Some(lyrics::parse_lrc(&tags.lyrics));
+        }
+    }
+    tags
+}
+
+impl From<AudioMetadata> for AudioTags {
+    fn from(meta: AudioMetadata) -> Self {
+        AudioTags {
+            title: meta.title,
+            artist: meta.artist,
+            album_artist: meta.album_artist,
+            album: meta.album,
+            genre: meta.genre,
+            year: meta.year,
+            track_number: meta.track_number,
+            total_tracks: meta.total_tracks,
+            disc_number: meta.disc_number,
+            total_discs: meta.total_discs,
+            composer: meta.composer,
+            comment: meta.comment,
+            lyrics: meta.lyrics,
+            bpm: meta.bpm,
+            copyright: meta.copyright,
+            publisher: meta.publisher,
+            isrc: meta.isrc,
+            cover_art: meta.cover_art,
+            artists: meta.artists,
+            album_artists: meta.album_artists,
+            genres: meta.genres,
+            composers: meta.composers,
+            replay_gain_track_gain: Some(meta.replay_gain_track_gain),
+            replay_gain_track_peak: Some(meta.replay_gain_track_peak),
+            replay_gain_album_gain: Some(meta.replay_gain_album_gain),
+            replay_gain_album_peak: Some(meta.replay_gain_album_peak),
+            musicbrainz_track_id: Some(meta.musicbrainz_track_id),
+            musicbrainz_release_id: Some(meta.musicbrainz_release_id),
+            musicbrainz_artist_id: Some(meta.musicbrainz_artist_id),
+            musicbrainz_release_group_id: Some(meta.musicbrainz_release_group_id),
+            original_year: Some(meta.original_year),
+            release_date: Some(meta.release_date),
+            catalog_number: Some(meta.catalog_number),
+            barcode: Some(meta.barcode),
+            label: Some(meta.label),
+            conductor: Some(meta.conductor),
+            lyricist: Some(meta.lyricist),
+            remixer: Some(meta.remixer),
+            grouping: Some(meta.grouping),
+            artist_sort: Some(meta.artist_sort),
+            album_sort: Some(meta.album_sort),
+            title_sort: Some(meta.title_sort),
+            media_type: Some(meta.media_type),
+            rating: Some(meta.rating),
+            play_count: Some(meta.play_count),
+            loved: Some(meta.loved),
+            custom_fields: None,
+            synced_lyrics: None,
+            remove_pictures: None,
+            cover_art_source: String::new(),
+        }
+    }
+}
+
+/// Values to write for a multi-value field whose file currently holds `stored`.
+/// The display string shows the values joined with `MULTI_VALUE_SEPARATOR`. As
+/// long as it still shows `stored` it is not split, so a single value that
+/// contains "; " survives; `list` is then written if one was sent. `list` is
+/// also used when it matches the display string. Only an edited display string
+/// is split on `;`.
+fn resolve_values(single: &str, list: &[String], stored: &[String]) -> Vec<String> {
+    let edited = single != stored.join(MULTI_VALUE_SEPARATOR);
+    if !list.is_empty() && (!edited || list.join(MULTI_VALUE_SEPARATOR) == single) {
+        return list.to_vec();
+    }
+    if !edited {
+        return stored.to_vec();
+    }
+    single
+        .split(';')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Check that a full release date agrees with the year being written.
+fn check_release_date(date: &str, year: i64) -> Result<(), String> {
+    if year > 0 && parse_year(date) != year {
+        return Err(format!(
+            "Release date {} does not match year {}",
+            date, year
+        ));
+    }
+    Ok(())
+}
+
+/// `tags` with every value in the form `write_tags_to_path` stores it, for
+/// comparing against the file's `stored` tags. Fails where the write would.
+pub(crate) fn normalize_for_write(
+    tags: &AudioTags,
+    stored: &AudioTags,
+) -> Result<AudioTags, String> {
+    let mut tags = tags.clone();
+    let multi_values = [
+        (&mut tags.artist, &mut tags.artists, &stored.artists),
+        (
+            &mut tags.album_artist,
+            &mut tags.album_artists,
+            &stored.album_artists,
+        ),
+        (&mut tags.genre, &mut tags.genres, &stored.genres),
+        (&mut tags.composer, &mut tags.composers, &stored.composers),
+    ];
+    for (single, list, stored) in multi_values {
+        let values = resolve_values(single, list, stored);
+        *single = values.join(MULTI_VALUE_SEPARATOR);
+        *list = values;
+    }
+
+    let gains = [
+        &mut tags.replay_gain_track_gain,
+        &mut tags.replay_gain_album_gain,
+    ];
+    for gain in gains.into_iter().flatten() {
+        *gain = format_replay_gain(gain);
+    }
+    let peaks = [
+        &mut tags.replay_gain_track_peak,
+        &mut tags.replay_gain_album_peak,
+    ];
+    for peak in peaks.into_iter().flatten() {
+        *peak = format_replay_peak(peak);
+    }
+
+    if let Some(date) = &tags.release_date {
+        if Some(date) != stored.release_date.as_ref() && !date.trim().is_empty() {
+            check_release_date(date, tags.year)?;
+        }
+    }
+    Ok(tags)
+}
+
+/// Outcome of writing tags to one file in a multi-file operation
+#[derive(Debug, Serialize, Clone)]
+pub struct TagWriteResult {
+    pub path: String,
+    pub success: bool,
+    /// Why the write failed, or for a successful one why it cannot be undone
+    pub error: Option<String>,
+}
+
+impl FileResult for TagWriteResult {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+}
+
+impl TagWriteResult {
+    pub(crate) fn from_result(path: &str, result: Result<(), String>) -> Self {
+        TagWriteResult {
+            path: path.to_string(),
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
 }
 
+/// Write tags to a single file. A new `cover_art` is downscaled / re-encoded
+/// per `artwork` before it is embedded.
 #[tauri::command]
-pub async fn write_audio_tags(path: String, tags: AudioTags) -> Result<(), String> {
+pub async fn write_audio_tags(
+    app: AppHandle,
+    path: String,
+    tags: AudioTags,
+    artwork: Option<ArtworkOptions>,
+    options: Option<TagWriteOptions>,
+) -> Result<(), String> {
+    let mut tags = tags;
+    if let Some(options) = &artwork {
+        if !is_current_cover(Path::new(&path), &tags.cover_art) {
+            tags.cover_art = shrink_data_uri(&tags.cover_art, options)?;
+        }
+    }
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let mut recorder = TagEditRecorder::begin(&app, "Edit tags")?.with_write_options(options);
+    let result = recorder.write(Path::new(&path), &tags);
+    recorder.finish()?;
+    result
+}
+
+/// Write `tags` into the file's primary tag. Empty strings / zero numbers remove
+/// the corresponding field; an empty `cover_art` leaves existing pictures alone
+/// unless `remove_pictures` selects them. `options` picks the ID3v2 version and
+/// what happens to ID3v1 / APE tags next to the primary one.
+///
+/// Those tags are written first, so that an error leaves the primary tag as
+/// it was and never follows a successful write of it.
+pub(crate) fn write_tags_to_path(
+    file_path: &Path,
+    tags: &AudioTags,
+    options: &TagWriteOptions,
+) -> Result<(), String> {
     use lofty::prelude::*;
     use lofty::probe::Probe;
 
-    let file_path = Path::new(&path);
+    let mut tags = tags.clone();
     if !file_path.exists() {
-        return Err(format!("File not found: {}", path));
+        return Err(format!("File not found: {}", file_path.display()));
     }
+    let removed_pictures = tags
+        .remove_pictures
+        .as_deref()
+        .map(PictureSelection::parse)
+        .transpose()?;
 
     let mut tagged = Probe::open(file_path)
         .and_then(|p| p.read())
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
+    let file_type = tagged.file_type();
+
     // Get or create primary tag
     let tag = match tagged.primary_tag_mut() {
         Some(t) => t,
         None => {
             // Determine the primary tag type for this file type
-            let file_type = tagged.file_type();
             let tag_type = file_type.primary_tag_type();
             tagged.insert_tag(lofty::tag::Tag::new(tag_type));
             tagged.primary_tag_mut().unwrap()
         }
     };
 
-    tag.set_title(tags.title);
-    tag.set_artist(tags.artist);
-    tag.set_album(tags.album);
-    tag.set_genre(tags.genre);
+    let popularity = PopularityUpdate {
+        rating: tags.rating,
+        play_count: tags.play_count,
+        loved: tags.loved,
+    };
+
+    // The generic tag cannot carry unknown TXXX frames, POPM, PCNT or SYLT, so
+    // for ID3v2 the original tag is kept and they are added to the saved tag
+    let mut id3v2_original = None;
+    let mut write_options = options.lofty_options(None);
+    if id3v2::uses_id3v2(file_type) {
+        let original = id3v2::read_tag(file_path, file_type)?;
+        write_options = options.lofty_options(original.as_ref());
+        if tags.custom_fields.is_none() {
+            tags.custom_fields = Some(
+                original
+                    .as_ref()
+                    .map(custom_fields::from_id3v2)
+                    .unwrap_or_default(),
+            );
+        }
+        if let Some(fields) = &tags.custom_fields {
+            custom_fields::validate(lofty::tag::TagType::Id3v2, fields)?;
+        }
+        id3v2_original = Some(original);
+    } else {
+        if let Some(fields) = tags.custom_fields.take() {
+            custom_fields::validate(tag.tag_type(), &fields)?;
+            custom_fields::apply_to_tag(tag, &fields);
+        }
+        ratings::apply_to_tag(tag, &popularity);
+        // Synced lines are stored as LRC text in the lyrics field. Lines that
+        // are still those of the stored lyrics leave the lyrics as sent
+        if let Some(lines) = tags.synced_lyrics.take() {
+            let stored = tag
+                .get_string(&lofty::tag::ItemKey::Lyrics)
+                .unwrap_or_default()
+                .to_string();
+            if lines != lyrics::parse_lrc(&stored) {
+                if !lines.is_empty() {
+                    tags.lyrics = lyrics::render_lrc(&lines);
+                } else if tags.lyrics == stored {
+                    // Removing the lines removes the LRC text, unless other
+                    // lyrics were sent with them
+                    tags.lyrics.clear();
+                }
+            }
+        }
+    }
+
+    // Taken before `year` is written, which may share the date's frame
+    let stored_release_date = tag
+        .get_string(&lofty::tag::ItemKey::RecordingDate)
+        .unwrap_or_default()
+        .to_string();
+
+    if tags.title.is_empty() {
+        tag.remove_title();
+    } else {
+        tag.set_title(tags.title);
+    }
+    if tags.album.is_empty() {
+        tag.remove_album();
+    } else {
+        tag.set_album(tags.album);
+    }
     if tags.year > 0 {
         tag.set_year(tags.year as u32);
     } else {
@@ -480,13 +1081,165 @@ pub async fn write_audio_tags(path: String, tags: AudioTags) -> Result<(), Strin
                 }
             };
         }
-        set_or_remove!(ItemKey::AlbumArtist, tags.album_artist);
-        set_or_remove!(ItemKey::Composer, tags.composer);
+        // Multi-value fields are written as one item per value so that lofty
+        // stores them in the native representation of each tag format
+        let multi_values = [
+            (ItemKey::TrackArtist, &tags.artist, &tags.artists),
+            (
+                ItemKey::AlbumArtist,
+                &tags.album_artist,
+                &tags.album_artists,
+            ),
+            (ItemKey::Genre, &tags.genre, &tags.genres),
+            (ItemKey::Composer, &tags.composer, &tags.composers),
+        ];
+        for (key, single, list) in multi_values {
+            let stored: Vec<String> = tag.get_strings(&key).map(str::to_string).collect();
+            let values = resolve_values(single, list, &stored);
+            tag.remove_key(&key);
+            for value in values {
+                tag.push(TagItem::new(key.clone(), ItemValue::Text(value)));
+            }
+        }
+
         set_or_remove!(ItemKey::Comment, tags.comment);
         set_or_remove!(ItemKey::Lyrics, tags.lyrics);
         set_or_remove!(ItemKey::CopyrightMessage, tags.copyright);
-        set_or_remove!(ItemKey::Label, tags.publisher);
         set_or_remove!(ItemKey::Isrc, tags.isrc);
+
+        let tag_type = tag.tag_type();
+        let replay_gain = [
+            (
+                ItemKey::ReplayGainTrackGain,
+                "replaygain_track_gain",
+                tags.replay_gain_track_gain
+                    .as_deref()
+                    .map(format_replay_gain),
+            ),
+            (
+                ItemKey::ReplayGainTrackPeak,
+                "replaygain_track_peak",
+                tags.replay_gain_track_peak
+                    .as_deref()
+                    .map(format_replay_peak),
+            ),
+            (
+                ItemKey::ReplayGainAlbumGain,
+                "replaygain_album_gain",
+                tags.replay_gain_album_gain
+                    .as_deref()
+                    .map(format_replay_gain),
+            ),
+            (
+                ItemKey::ReplayGainAlbumPeak,
+                "replaygain_album_peak",
+                tags.replay_gain_album_peak
+                    .as_deref()
+                    .map(format_replay_peak),
+            ),
+        ];
+        for (key, atom, value) in replay_gain {
+            let Some(value) = value else { continue };
+            if tag_type == lofty::tag::TagType::Mp4Ilst {
+                // Drop an upper-case variant written by another tagger
+                tag.remove_key(&ItemKey::Unknown(format!(
+                    "{}{}",
+                    MP4_FREEFORM_PREFIX,
+                    atom.to_uppercase()
+                )));
+            }
+            set_or_remove!(mp4_freeform_key(tag_type, key, atom), value);
+        }
+
+        let extended = [
+            (
+                ItemKey::MusicBrainzRecordingId,
+                "MusicBrainz Track Id",
+                tags.musicbrainz_track_id,
+            ),
+            (
+                ItemKey::MusicBrainzReleaseId,
+                "MusicBrainz Album Id",
+                tags.musicbrainz_release_id,
+            ),
+            (
+                ItemKey::MusicBrainzArtistId,
+                "MusicBrainz Artist Id",
+                tags.musicbrainz_artist_id,
+            ),
+            (
+                ItemKey::MusicBrainzReleaseGroupId,
+                "MusicBrainz Release Group Id",
+                tags.musicbrainz_release_group_id,
+            ),
+            (ItemKey::CatalogNumber, "CATALOGNUMBER", tags.catalog_number),
+            (ItemKey::Barcode, "BARCODE", tags.barcode),
+            (ItemKey::Conductor, "CONDUCTOR", tags.conductor),
+            (ItemKey::Lyricist, "LYRICIST", tags.lyricist),
+            (ItemKey::Remixer, "REMIXER", tags.remixer),
+            (ItemKey::OriginalMediaType, "MEDIA", tags.media_type),
+        ];
+        for (key, atom, value) in extended {
+            if let Some(value) = value {
+                set_or_remove!(mp4_freeform_key(tag_type, key, atom), value);
+            }
+        }
+        let other_fields = [
+            (ItemKey::ContentGroup, tags.grouping),
+            (ItemKey::TrackArtistSortOrder, tags.artist_sort),
+            (ItemKey::AlbumTitleSortOrder, tags.album_sort),
+            (ItemKey::TrackTitleSortOrder, tags.title_sort),
+        ];
+        for (key, value) in other_fields {
+            if let Some(value) = value {
+                set_or_remove!(key, value);
+            }
+        }
+
+        // Publisher and label share a field; an edit to either one is written
+        let label_key = mp4_freeform_key(tag_type, ItemKey::Label, "LABEL");
+        let current_label = tag.get_string(&label_key).unwrap_or_default().to_string();
+        let label = match tags.label {
+            Some(label) if label != current_label => label,
+            _ => tags.publisher,
+        };
+        set_or_remove!(label_key, label);
+
+        // A full original date is kept as long as its year was not changed
+        let original_key = mp4_freeform_key(tag_type, ItemKey::OriginalReleaseDate, "ORIGINALDATE");
+        match tags.original_year {
+            Some(year) if year > 0 => {
+                let current = tag.get_string(&original_key).unwrap_or_default();
+                if parse_year(current) != year {
+                    tag.insert(TagItem::new(
+                        original_key,
+                        ItemValue::Text(year.to_string()),
+                    ));
+                }
+            }
+            Some(_) => {
+                tag.remove_key(&original_key);
+            }
+            None => {}
+        }
+
+        // The full date shares its frame with the year in most formats, so the
+        // year is put back after the date is removed, and an unchanged date is
+        // left to `year` in case that was edited
+        match tags.release_date {
+            Some(date) if date == stored_release_date => {}
+            Some(date) if date.trim().is_empty() => {
+                tag.remove_key(&ItemKey::RecordingDate);
+                if tags.year > 0 {
+                    tag.set_year(tags.year as u32);
+                }
+            }
+            Some(date) => {
+                check_release_date(&date, tags.year)?;
+                tag.insert(TagItem::new(ItemKey::RecordingDate, ItemValue::Text(date)));
+            }
+            None => {}
+        }
         if tags.bpm > 0 {
             tag.insert(TagItem::new(
                 ItemKey::Bpm,
@@ -498,28 +1251,159 @@ pub async fn write_audio_tags(path: String, tags: AudioTags) -> Result<(), Strin
     }
 
     // Handle cover art
+    let is_front =
+        |pic: &&lofty::picture::Picture| pic.pic_type() == lofty::picture::PictureType::CoverFront;
+    let current_cover = tag.pictures().iter().find(is_front).cloned();
+    if let Some(selection) = &removed_pictures {
+        selection.remove_from(tag);
+    }
     if !tags.cover_art.is_empty() {
-        // Parse data URI: data:image/jpeg;base64,...
-        if let Some(data) = parse_data_uri(&tags.cover_art) {
-            // Remove existing pictures
-            tag.remove_picture_type(lofty::picture::PictureType::CoverFront);
-
-            let pic = lofty::picture::Picture::new_unchecked(
-                lofty::picture::PictureType::CoverFront,
-                Some(data.mime_type),
-                None,
-                data.bytes,
-            );
-            tag.push_picture(pic);
+        let bytes = decode_data_uri(&tags.cover_art)?;
+        match current_cover.filter(|pic| pic.data() == bytes.as_slice()) {
+            // The cover as read from the file is kept as it is, without the
+            // checks a new cover has to pass
+            Some(pic) => {
+                if !tag.pictures().iter().any(|p| is_front(&p)) {
+                    tag.push_picture(pic);
+                }
+            }
+            None => {
+                let data = parse_data_uri(&tags.cover_art)?;
+                check_picture_format(tag.tag_type(), &data.mime_type)?;
+                tag.remove_picture_type(lofty::picture::PictureType::CoverFront);
+
+                let pic = lofty::picture::Picture::new_unchecked(
+                    lofty::picture::PictureType::CoverFront,
+                    Some(data.mime_type),
+                    None,
+                    data.bytes,
+                );
+                tag.push_picture(pic);
+            }
         }
     }
 
-    tag.save_to_path(&path, lofty::config::WriteOptions::default())
-        .map_err(|e| format!("Failed to save tags: {}", e))?;
+    options.apply_secondary_tags(file_path, file_type, tag)?;
 
+    match id3v2_original {
+        Some(original) => {
+            let mut id3 = id3v2::from_generic(tag, original.as_ref());
+            custom_fields::apply_to_id3v2(&mut id3, &tags.custom_fields.unwrap_or_default());
+            ratings::apply_to_id3v2(&mut id3, &popularity);
+            // Unchanged lines keep the original frame with its language and description
+            let unchanged = original
+                .as_ref()
+                .map(lyrics::from_id3v2)
+                .unwrap_or_default();
+            if let Some(lines) = tags.synced_lyrics.filter(|lines| *lines != unchanged) {
+                lyrics::apply_to_id3v2(&mut id3, &lines);
+            }
+            id3v2::save_tag(file_path, &id3, write_options)?;
+        }
+        None => {
+            tag.save_to_path(file_path, write_options)
+                .map_err(|e| format!("Failed to save tags: {}", e))?;
+        }
+    }
     Ok(())
 }
 
+/// Raw front cover of the file's primary tag as `(mime type, bytes)`.
+pub(crate) fn read_front_cover(file_path: &Path) -> Option<(String, Vec<u8>)> {
+    use lofty::prelude::*;
+    use lofty::probe::Probe;
+
+    let tagged = Probe::open(file_path).and_then(|p| p.read()).ok()?;
+    let tag = tagged.primary_tag()?;
+    let pic = tag
+        .pictures()
+        .iter()
+        .find(|p| p.pic_type() == lofty::picture::PictureType::CoverFront)?;
+
+    Some((picture_mime(pic), pic.data().to_vec()))
+}
+
+/// Replace the front cover of the file's primary tag, or remove it when `cover`
+/// is `None`, saving per `options`.
+pub(crate) fn set_front_cover(
+    file_path: &Path,
+    cover: Option<(String, Vec<u8>)>,
+    options: &TagWriteOptions,
+) -> Result<(), String> {
+    use lofty::prelude::*;
+    use lofty::probe::Probe;
+
+    let mut tagged = Probe::open(file_path)
+        .and_then(|p| p.read())
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let file_type = tagged.file_type();
+
+    let tag = match tagged.primary_tag_mut() {
+        Some(t) => t,
+        // No tag at all means there is no cover to remove
+        None if cover.is_none() => return Ok(()),
+        None => {
+            tagged.insert_tag(lofty::tag::Tag::new(file_type.primary_tag_type()));
+            tagged.primary_tag_mut().unwrap()
+        }
+    };
+
+    let cover = match cover {
+        Some((mime, bytes)) => {
+            let mime_type = sniff_image_mime(&bytes)
+                .or_else(|| (!mime.is_empty()).then(|| lofty::picture::MimeType::from_str(&mime)))
+                .ok_or(NOT_AN_IMAGE)?;
+            check_picture_format(tag.tag_type(), &mime_type)?;
+            Some((mime_type, bytes))
+        }
+        None => None,
+    };
+
+    tag.remove_picture_type(lofty::picture::PictureType::CoverFront);
+    if let Some((mime_type, bytes)) = cover {
+        tag.push_picture(lofty::picture::Picture::new_unchecked(
+            lofty::picture::PictureType::CoverFront,
+            Some(mime_type),
+            None,
+            bytes,
+        ));
+    }
+
+    save_primary_tag(file_path, file_type, tag, options)
+}
+
+/// Save an edited primary tag per `options`, as `write_tags_to_path` does: the
+/// ID3v1 / APE tags first, then the primary tag. ID3v2 frames the generic tag
+/// cannot carry (custom TXXX, POPM, PCNT, SYLT, ...) are copied back from the
+/// file as it was.
+pub(crate) fn save_primary_tag(
+    file_path: &Path,
+    file_type: lofty::file::FileType,
+    tag: &lofty::tag::Tag,
+    options: &TagWriteOptions,
+) -> Result<(), String> {
+    use lofty::prelude::*;
+
+    let original = if id3v2::uses_id3v2(file_type) {
+        id3v2::read_tag(file_path, file_type)?
+    } else {
+        None
+    };
+
+    options.apply_secondary_tags(file_path, file_type, tag)?;
+    let write_options = options.lofty_options(original.as_ref());
+    if id3v2::uses_id3v2(file_type) {
+        id3v2::save_tag(
+            file_path,
+            &id3v2::from_generic(tag, original.as_ref()),
+            write_options,
+        )
+    } else {
+        tag.save_to_path(file_path, write_options)
+            .map_err(|e| format!("Failed to save tags: {}", e))
+    }
+}
+
 #[tauri::command]
 pub async fn open_path(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
@@ -578,30 +1462,90 @@ pub async fn reveal_item_in_dir(path: String) -> Result<(), String> {
     Ok(())
 }
 
-struct DataUriParts {
-    mime_type: lofty::picture::MimeType,
-    bytes: Vec<u8>,
+pub(crate) struct DataUriParts {
+    pub mime_type: lofty::picture::MimeType,
+    pub bytes: Vec<u8>,
 }
 
-fn parse_data_uri(uri: &str) -> Option<DataUriParts> {
-    use base64::Engine;
+const NOT_AN_IMAGE: &str = "Unsupported image data: expected JPEG, PNG, GIF, BMP, TIFF or WebP";
+
+/// Image format of `bytes` from its magic bytes, `None` if it is not a supported image.
+pub(crate) fn sniff_image_mime(bytes: &[u8]) -> Option<lofty::picture::MimeType> {
+    use lofty::picture::MimeType;
+
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(MimeType::Jpeg)
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(MimeType::Png)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(MimeType::Gif)
+    } else if bytes.starts_with(b"BM") && bytes.len() >= 26 {
+        Some(MimeType::Bmp)
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        Some(MimeType::Tiff)
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(MimeType::Unknown(WEBP_MIME.to_string()))
+    } else {
+        None
+    }
+}
+
+pub(crate) const WEBP_MIME: &str = "image/webp";
 
-    // Expected format: data:image/jpeg;base64,<data>
-    let uri = uri.strip_prefix("data:")?;
-    let (header, data) = uri.split_once(";base64,")?;
-
-    let mime_type = match header {
-        "image/jpeg" => lofty::picture::MimeType::Jpeg,
-        "image/png" => lofty::picture::MimeType::Png,
-        "image/bmp" => lofty::picture::MimeType::Bmp,
-        "image/gif" => lofty::picture::MimeType::Gif,
-        "image/tiff" => lofty::picture::MimeType::Tiff,
-        _ => lofty::picture::MimeType::Jpeg,
+/// Reject picture formats the tag type cannot store. MP4 `covr` atoms only
+/// know JPEG, PNG, GIF and BMP; ID3v2, Vorbis comments and APE take any image.
+pub(crate) fn check_picture_format(
+    tag_type: lofty::tag::TagType,
+    mime: &lofty::picture::MimeType,
+) -> Result<(), String> {
+    use lofty::picture::MimeType;
+
+    let supported = match tag_type {
+        lofty::tag::TagType::Mp4Ilst => matches!(
+            mime,
+            MimeType::Jpeg | MimeType::Png | MimeType::Gif | MimeType::Bmp
+        ),
+        _ => true,
     };
+    if !supported {
+        return Err(format!(
+            "{} pictures cannot be stored in MP4 files; use JPEG or PNG",
+            mime.as_str()
+        ));
+    }
+    Ok(())
+}
+
+/// The bytes of a `data:<mime>;base64,<data>` URI, whatever they hold.
+fn decode_data_uri(uri: &str) -> Result<Vec<u8>, String> {
+    use base64::Engine;
 
-    let bytes = base64::engine::general_purpose::STANDARD
+    let (_, data) = uri
+        .strip_prefix("data:")
+        .and_then(|u| u.split_once(";base64,"))
+        .ok_or("Picture data must be a base64 data URI")?;
+    base64::engine::general_purpose::STANDARD
         .decode(data)
-        .ok()?;
+        .map_err(|e| format!("Invalid base64 picture data: {}", e))
+}
+
+/// Whether `uri` holds exactly the file's current front cover, i.e. the cover
+/// was read and sent back unchanged.
+pub(crate) fn is_current_cover(path: &Path, uri: &str) -> bool {
+    if uri.is_empty() {
+        return false;
+    }
+    match (decode_data_uri(uri), read_front_cover(path)) {
+        (Ok(bytes), Some((_, current))) => bytes == current,
+        _ => false,
+    }
+}
+
+/// Decode a `data:<mime>;base64,<data>` image. The format is taken from the
+/// bytes; the declared MIME type is ignored.
+pub(crate) fn parse_data_uri(uri: &str) -> Result<DataUriParts, String> {
+    let bytes = decode_data_uri(uri)?;
+    let mime_type = sniff_image_mime(&bytes).ok_or(NOT_AN_IMAGE)?;
 
-    Some(DataUriParts { mime_type, bytes })
+    Ok(DataUriParts { mime_type, bytes })
 }
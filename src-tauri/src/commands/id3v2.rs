@@ -0,0 +1,125 @@
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::file::{AudioFile, FileType};
+use lofty::id3::v2::{Frame, FrameId, Id3v2Tag};
+use lofty::tag::{Tag, TagExt};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::Path;
+
+/// Whether the file keeps its tags in ID3v2. Frames the generic tag cannot
+/// represent (unknown TXXX, POPM, PCNT, SYLT, PRIV, ...) are dropped by a generic save, so these
+/// files are saved through the concrete tag type with those frames carried over.
+pub(crate) fn uses_id3v2(file_type: FileType) -> bool {
+    matches!(
+        file_type,
+        FileType::Mpeg | FileType::Wav | FileType::Aiff | FileType::Aac
+    )
+}
+
+/// Concrete file types that carry an ID3v2 tag.
+trait Id3v2File: AudioFile {
+    fn id3v2_tag(&self) -> Option<&Id3v2Tag>;
+}
+
+macro_rules! impl_id3v2_file {
+    ($($file:ty),*) => {
+        $(
+            impl Id3v2File for $file {
+                fn id3v2_tag(&self) -> Option<&Id3v2Tag> {
+                    self.id3v2()
+                }
+            }
+        )*
+    };
+}
+
+impl_id3v2_file!(
+    lofty::mpeg::MpegFile,
+    lofty::iff::wav::WavFile,
+    lofty::iff::aiff::AiffFile,
+    lofty::aac::AacFile
+);
+
+fn parse_options() -> ParseOptions {
+    ParseOptions::new().read_properties(false)
+}
+
+fn read<F: Id3v2File>(path: &Path) -> Result<Option<Id3v2Tag>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let parsed = F::read_from(&mut file, parse_options())
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(parsed.id3v2_tag().cloned())
+}
+
+/// The file's ID3v2 tag with every frame, or `None` if it has none.
+pub(crate) fn read_tag(path: &Path, file_type: FileType) -> Result<Option<Id3v2Tag>, String> {
+    match file_type {
+        FileType::Mpeg => read::<lofty::mpeg::MpegFile>(path),
+        FileType::Wav => read::<lofty::iff::wav::WavFile>(path),
+        FileType::Aiff => read::<lofty::iff::aiff::AiffFile>(path),
+        FileType::Aac => read::<lofty::aac::AacFile>(path),
+        _ => Ok(None),
+    }
+}
+
+/// What tells frames of the same id apart. Comments and lyrics differ by
+/// description only, as the generic tag does not keep their language.
+fn frame_key(frame: &Frame<'_>) -> String {
+    match frame {
+        Frame::UserText(f) => format!("TXXX\0{}", f.description),
+        Frame::UserUrl(f) => format!("WXXX\0{}", f.description),
+        Frame::Comment(f) => format!("COMM\0{}", f.description),
+        Frame::UnsynchronizedText(f) => format!("USLT\0{}", f.description),
+        _ => frame.id_str().to_string(),
+    }
+}
+
+/// The ID3v2 tag to save for the generic `tag` of a file whose ID3v2 tag was
+/// `original`. Only the frames the generic tag carries are replaced; every
+/// other frame of `original` (POPM, PCNT, SYLT, unknown TXXX, PRIV, GEOB, ...)
+/// is kept, so that callers can edit them and write everything with a single
+/// save.
+pub(crate) fn from_generic(tag: &Tag, original: Option<&Id3v2Tag>) -> Id3v2Tag {
+    let converted = Id3v2Tag::from(tag.clone());
+    let Some(original) = original else {
+        return converted;
+    };
+    // The frames of `original` that survive a trip through the generic tag
+    let carried: HashSet<String> = Id3v2Tag::from(Tag::from(original.clone()))
+        .iter()
+        .map(frame_key)
+        .collect();
+
+    let mut id3 = original.clone();
+    id3.retain(|frame| !carried.contains(&frame_key(frame)));
+    for frame in converted.iter().cloned() {
+        id3.insert(frame);
+    }
+    keep_frames(&mut id3, Some(original), &["POPM", "PCNT", "SYLT"]);
+    id3
+}
+
+/// Save `tag` as the file's ID3v2 tag, replacing the one it has.
+pub(crate) fn save_tag(path: &Path, tag: &Id3v2Tag, options: WriteOptions) -> Result<(), String> {
+    tag.save_to_path(path, options)
+        .map_err(|e| format!("Failed to save tags: {}", e))
+}
+
+/// Replace the frames with the given ids by the ones `original` had, e.g. to
+/// put back frames a generic save dropped.
+pub(crate) fn keep_frames(tag: &mut Id3v2Tag, original: Option<&Id3v2Tag>, ids: &[&str]) {
+    for id in ids {
+        tag.remove(&FrameId::Valid(Cow::Borrowed(id)))
+            .for_each(drop);
+    }
+    let frames: Vec<Frame<'static>> = original
+        .into_iter()
+        .flatten()
+        .filter(|frame| ids.contains(&frame.id_str()))
+        .cloned()
+        .collect();
+    for frame in frames {
+        tag.insert(frame);
+    }
+}
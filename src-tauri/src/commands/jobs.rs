@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Cancellation flags of the background jobs that are currently running.
+#[derive(Default)]
+pub struct BackgroundJobs {
+    flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    counter: AtomicU64,
+}
+
+impl BackgroundJobs {
+    /// Register a new job and return its id with the flag it should poll.
+    pub(crate) fn start(&self, kind: &str) -> (String, Arc<AtomicBool>) {
+        let id = format!("{}-{}", kind, self.counter.fetch_add(1, Ordering::Relaxed));
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.insert(id.clone(), flag.clone());
+        }
+        (id, flag)
+    }
+
+    pub(crate) fn finish(&self, id: &str) {
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.remove(id);
+        }
+    }
+}
+
+/// Ask a running background job to stop. Returns false if no such job is running.
+#[tauri::command]
+pub async fn cancel_job(jobs: State<'_, BackgroundJobs>, job_id: String) -> Result<bool, String> {
+    let flags = jobs.flags.lock().map_err(|e| e.to_string())?;
+    match flags.get(&job_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
@@ -0,0 +1,716 @@
+use crate::commands::file_times::{modified_millis, record_tag_edits, SavedTimes};
+use crate::commands::fs::{
+    picture_mime, read_tags_for_edit, set_front_cover, write_tags_to_path, AudioTags,
+};
+use crate::commands::m3u::write_playlists_atomically;
+use crate::commands::pictures::{read_pictures, write_pictures};
+use crate::commands::write_options::TagWriteOptions;
+use lofty::config::WriteOptions;
+use lofty::picture::{MimeType, Picture, PictureType};
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::{ItemKey, ItemValue, Tag, TagItem, TagType};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Maximum number of transactions kept in the journal; older ones are pruned
+const MAX_TRANSACTIONS: usize = 200;
+
+/// An ID3v1 tag is the last 128 bytes of the file
+const ID3V1_SIZE: u64 = 128;
+
+static TRANSACTION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Blobs stored by recorders whose transaction is not saved yet, with the number
+/// of recorders holding each. Pruning must not remove them.
+fn pending_blobs() -> MutexGuard<'static, HashMap<String, usize>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
+    PENDING
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileMove {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlaylistChange {
+    pub path: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// One embedded picture of a snapshot; the image data lives in the blob directory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PictureBlob {
+    #[serde(rename = "pictureType")]
+    pub picture_type: u8,
+    pub description: Option<String>,
+    pub mime: String,
+    pub blob: String,
+}
+
+/// An ID3v1 or APE tag as it was before a write replaced or removed it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SecondaryTagSnapshot {
+    /// "ID3v1" | "APE"
+    #[serde(rename = "tagType")]
+    pub tag_type: String,
+    /// Whether the file had the tag; undo removes it otherwise
+    pub present: bool,
+    /// ID3v1: blob of the raw tag
+    pub blob: Option<String>,
+    /// APE: key and text of every text item
+    #[serde(default)]
+    pub items: Vec<(String, String)>,
+    /// APE: pictures
+    #[serde(default)]
+    pub pictures: Vec<PictureBlob>,
+}
+
+/// Tag state of one file before it was edited. Pictures are stored once per
+/// distinct image in the journal's blob directory instead of inline.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TagSnapshot {
+    pub path: String,
+    /// Previous tags; `cover_art` is always empty here
+    pub tags: AudioTags,
+    /// Hash of the previous front cover blob, `None` if there was no front cover.
+    /// Only set by snapshots taken before `pictures` existed.
+    #[serde(rename = "coverBlob")]
+    pub cover_blob: Option<String>,
+    #[serde(rename = "coverMime", default)]
+    pub cover_mime: String,
+    /// Every previous picture in tag order; `None` for older snapshots
+    #[serde(default)]
+    pub pictures: Option<Vec<PictureBlob>>,
+    /// ID3v1 / APE tags the write replaced or removed
+    #[serde(rename = "secondaryTags", default)]
+    pub secondary_tags: Vec<SecondaryTagSnapshot>,
+}
+
+/// A single undoable operation recorded in the journal.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Transaction {
+    pub id: String,
+    /// "move" | "tag-edit"
+    pub kind: String,
+    pub label: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+    #[serde(default)]
+    pub undone: bool,
+    #[serde(rename = "fileMoves", default)]
+    pub file_moves: Vec<FileMove>,
+    #[serde(rename = "playlistChanges", default)]
+    pub playlist_changes: Vec<PlaylistChange>,
+    #[serde(rename = "tagSnapshots", default)]
+    pub tag_snapshots: Vec<TagSnapshot>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TransactionSummary {
+    pub id: String,
+    pub kind: String,
+    pub label: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+    pub undone: bool,
+    #[serde(rename = "fileCount")]
+    pub file_count: usize,
+    #[serde(rename = "playlistCount")]
+    pub playlist_count: usize,
+}
+
+impl Transaction {
+    pub(crate) fn new(kind: &str, label: &str) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let seq = TRANSACTION_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        Transaction {
+            id: format!("{}-{}", created_at, seq),
+            kind: kind.to_string(),
+            label: label.to_string(),
+            created_at,
+            undone: false,
+            file_moves: Vec::new(),
+            playlist_changes: Vec::new(),
+            tag_snapshots: Vec::new(),
+        }
+    }
+
+    fn summary(&self) -> TransactionSummary {
+        TransactionSummary {
+            id: self.id.clone(),
+            kind: self.kind.clone(),
+            label: self.label.clone(),
+            created_at: self.created_at,
+            undone: self.undone,
+            file_count: self.file_moves.len() + self.tag_snapshots.len(),
+            playlist_count: self.playlist_changes.len(),
+        }
+    }
+}
+
+fn journal_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join("journal");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+    Ok(dir)
+}
+
+fn blob_dir(journal: &Path) -> Result<PathBuf, String> {
+    let dir = journal.join("blobs");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+    Ok(dir)
+}
+
+fn transaction_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    // Ids are generated by us; reject anything that could escape the journal directory
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(format!("Invalid transaction id: {}", id));
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+/// Persist a transaction and prune the oldest entries beyond `MAX_TRANSACTIONS`.
+pub(crate) fn save_transaction(app: &AppHandle, tx: &Transaction) -> Result<(), String> {
+    let dir = journal_dir(app)?;
+    let json = serde_json::to_string(tx).map_err(|e| e.to_string())?;
+    fs::write(transaction_path(&dir, &tx.id)?, json)
+        .map_err(|e| format!("Failed to write journal: {}", e))?;
+
+    let mut all = read_all_transactions(&dir);
+    if all.len() > MAX_TRANSACTIONS {
+        all.sort_by_key(|t| t.created_at);
+        let (pruned, kept) = all.split_at(all.len() - MAX_TRANSACTIONS);
+        for old in pruned {
+            let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+        }
+        remove_unreferenced_blobs(&dir, kept);
+    }
+    Ok(())
+}
+
+fn remove_unreferenced_blobs(dir: &Path, kept: &[Transaction]) {
+    let referenced: HashSet<&str> = kept
+        .iter()
+        .flat_map(|t| &t.tag_snapshots)
+        .flat_map(|s| {
+            let secondary = s.secondary_tags.iter().flat_map(|t| {
+                t.blob
+                    .as_deref()
+                    .into_iter()
+                    .chain(t.pictures.iter().map(|p| p.blob.as_str()))
+            });
+            s.cover_blob
+                .as_deref()
+                .into_iter()
+                .chain(s.pictures.iter().flatten().map(|p| p.blob.as_str()))
+                .chain(secondary)
+        })
+        .collect();
+
+    let Ok(entries) = fs::read_dir(dir.join("blobs")) else {
+        return;
+    };
+    // Held throughout so that no recorder can pick up a blob while it is removed
+    let pending = pending_blobs();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Blobs of a tag edit that is still running are not referenced yet
+        if !referenced.contains(name.as_str()) && !pending.contains_key(&name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn load_transaction(dir: &Path, id: &str) -> Result<Transaction, String> {
+    let content = fs::read_to_string(transaction_path(dir, id)?)
+        .map_err(|_| format!("Transaction not found: {}", id))?;
+    serde_json::from_str(&content).map_err(|e| format!("Corrupted transaction {}: {}", id, e))
+}
+
+fn read_all_transactions(dir: &Path) -> Vec<Transaction> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|c| serde_json::from_str::<Transaction>(&c).ok())
+        .collect()
+}
+
+/// Outcome of one file of a multi-file tag edit, which can take the journal's error.
+pub(crate) trait FileResult {
+    fn path(&self) -> &str;
+    fn error_mut(&mut self) -> &mut Option<String>;
+}
+
+/// Records the previous tag state of every file touched by one tag-editing
+/// command, so that the whole command can be rolled back as one transaction.
+pub(crate) struct TagEditRecorder<'a> {
+    app: &'a AppHandle,
+    blobs: PathBuf,
+    tx: Transaction,
+    options: TagWriteOptions,
+    /// Written files with their modification time before the write
+    edited: Vec<(String, u64)>,
+    /// Blobs this recorder registered in `pending_blobs`
+    blob_hashes: HashSet<String>,
+}
+
+impl<'a> TagEditRecorder<'a> {
+    pub(crate) fn begin(app: &'a AppHandle, label: &str) -> Result<Self, String> {
+        Ok(TagEditRecorder {
+            app,
+            blobs: blob_dir(&journal_dir(app)?)?,
+            tx: Transaction::new("tag-edit", label),
+            options: TagWriteOptions::default(),
+            edited: Vec::new(),
+            blob_hashes: HashSet::new(),
+        })
+    }
+
+    /// Use `options` for every `write` of this recorder.
+    pub(crate) fn with_write_options(mut self, options: TagWriteOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Store `bytes` in the blob directory and return its hash. The blob is
+    /// protected from pruning until the recorder is dropped.
+    fn store_blob(&mut self, bytes: &[u8]) -> Result<String, String> {
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        let mut pending = pending_blobs();
+        if self.blob_hashes.insert(hash.clone()) {
+            *pending.entry(hash.clone()).or_insert(0) += 1;
+        }
+        let blob = self.blobs.join(&hash);
+        if !blob.exists() {
+            fs::write(&blob, bytes).map_err(|e| format!("Failed to write journal: {}", e))?;
+        }
+        Ok(hash)
+    }
+
+    fn picture_blobs(&mut self, pictures: &[Picture]) -> Result<Vec<PictureBlob>, String> {
+        pictures
+            .iter()
+            .map(|pic| {
+                Ok(PictureBlob {
+                    picture_type: pic.pic_type().as_u8(),
+                    description: pic.description().map(str::to_string),
+                    mime: picture_mime(pic),
+                    blob: self.store_blob(pic.data())?,
+                })
+            })
+            .collect()
+    }
+
+    /// The ID3v1 / APE tags the write options will replace or remove.
+    fn snapshot_secondary_tags(
+        &mut self,
+        path: &Path,
+    ) -> Result<Vec<SecondaryTagSnapshot>, String> {
+        if !self.options.changes_secondary_tags() {
+            return Ok(Vec::new());
+        }
+        let tagged = Probe::open(path)
+            .and_then(|p| p.read())
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+
+        let mut snapshots = Vec::new();
+        for tag_type in self.options.changed_secondary_tags(tagged.file_type()) {
+            let mut snapshot = SecondaryTagSnapshot {
+                tag_type: secondary_tag_name(tag_type).to_string(),
+                present: false,
+                blob: None,
+                items: Vec::new(),
+                pictures: Vec::new(),
+            };
+            if let Some(tag) = tagged.tag(tag_type) {
+                snapshot.present = true;
+                if tag_type == TagType::Id3v1 {
+                    // ID3v1 has no item keys, so it is kept byte for byte
+                    let raw = read_raw_id3v1(path)?;
+                    snapshot.blob = raw.map(|raw| self.store_blob(&raw)).transpose()?;
+                } else {
+                    snapshot.items = tag
+                        .items()
+                        .filter_map(|item| {
+                            let key = item.key().map_key(tag_type, true)?;
+                            Some((key.to_string(), item.value().text()?.to_string()))
+                        })
+                        .collect();
+                    snapshot.pictures = self.picture_blobs(tag.pictures())?;
+                }
+            }
+            snapshots.push(snapshot);
+        }
+        Ok(snapshots)
+    }
+
+    /// The file's tags as stored, without display fallbacks such as the file
+    /// name for a missing title, so that restoring them changes nothing else.
+    fn snapshot(&mut self, path: &Path) -> Result<TagSnapshot, String> {
+        let mut tags = read_tags_for_edit(path, false);
+        tags.cover_art = String::new();
+
+        let pictures = self.picture_blobs(&read_pictures(path)?)?;
+        let secondary_tags = self.snapshot_secondary_tags(path)?;
+
+        Ok(TagSnapshot {
+            path: path.to_string_lossy().to_string(),
+            tags,
+            cover_blob: None,
+            cover_mime: String::new(),
+            pictures: Some(pictures),
+            secondary_tags,
+        })
+    }
+
+    /// Snapshot the file's current tags, then run `edit` on it. With
+    /// `preserve_times` set the file keeps its access and modification time.
+    pub(crate) fn edit(
+        &mut self,
+        path: &Path,
+        edit: impl FnOnce(&Path) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if !path.exists() {
+            return Err(format!("File not found: {}", path.display()));
+        }
+        let snapshot = self.snapshot(path)?;
+        let times = SavedTimes::read(path);
+        let result = edit(path);
+        if let (true, Some(times)) = (self.options.preserve_times, &times) {
+            let restored = times.restore(path);
+            if result.is_ok() {
+                restored?;
+            }
+        }
+        // ID3v1 / APE tags are written before the primary tag, so a failed
+        // write may have changed them; it stays undoable then
+        if result.is_ok() || !snapshot.secondary_tags.is_empty() {
+            self.tx.tag_snapshots.push(snapshot);
+        }
+        result?;
+        if let Some(times) = times {
+            self.edited
+                .push((path.to_string_lossy().to_string(), times.modified_millis()));
+        }
+        Ok(())
+    }
+
+    /// Snapshot the file's current tags, then write `tags` into it.
+    pub(crate) fn write(&mut self, path: &Path, tags: &AudioTags) -> Result<(), String> {
+        let options = self.options.clone();
+        self.edit(path, |p| write_tags_to_path(p, tags, &options))
+    }
+
+    /// Snapshot the file's current tags, then replace its pictures.
+    pub(crate) fn write_pictures(
+        &mut self,
+        path: &Path,
+        pictures: Vec<Picture>,
+    ) -> Result<(), String> {
+        let options = self.options.clone();
+        self.edit(path, |p| write_pictures(p, pictures, &options))
+    }
+
+    /// Snapshot the file's current tags, then replace or remove its front cover.
+    pub(crate) fn set_front_cover(
+        &mut self,
+        path: &Path,
+        cover: Option<(String, Vec<u8>)>,
+    ) -> Result<(), String> {
+        let options = self.options.clone();
+        self.edit(path, |p| set_front_cover(p, cover, &options))
+    }
+
+    /// Save the transaction if at least one file was written.
+    pub(crate) fn finish(self) -> Result<(), String> {
+        if self.tx.tag_snapshots.is_empty() {
+            return Ok(());
+        }
+        // The index only refines "recently added"; the edit itself succeeded
+        let _ = record_tag_edits(self.app, &self.edited);
+        save_transaction(self.app, &self.tx)
+            .map_err(|e| format!("Tags were written but the undo journal failed: {}", e))
+    }
+
+    /// `finish` for commands that report one result per file. The writes stand
+    /// even if the journal cannot be saved, so its error goes to the result of
+    /// every written file instead of failing the whole command.
+    pub(crate) fn finish_with_results(self, results: &mut [impl FileResult]) {
+        let written: HashSet<String> = self
+            .tx
+            .tag_snapshots
+            .iter()
+            .map(|s| s.path.clone())
+            .collect();
+        if let Err(e) = self.finish() {
+            for result in results.iter_mut().filter(|r| written.contains(r.path())) {
+                let error = result.error_mut();
+                if error.is_none() {
+                    *error = Some(e.clone());
+                }
+            }
+        }
+    }
+}
+
+impl Drop for TagEditRecorder<'_> {
+    fn drop(&mut self) {
+        let mut pending = pending_blobs();
+        for hash in &self.blob_hashes {
+            if let Some(count) = pending.get_mut(hash) {
+                *count -= 1;
+                if *count == 0 {
+                    pending.remove(hash);
+                }
+            }
+        }
+    }
+}
+
+fn secondary_tag_name(tag_type: TagType) -> &'static str {
+    match tag_type {
+        TagType::Id3v1 => "ID3v1",
+        _ => "APE",
+    }
+}
+
+/// The raw ID3v1 tag at the end of the file, if it has one.
+fn read_raw_id3v1(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len < ID3V1_SIZE {
+        return Ok(None);
+    }
+    let mut raw = vec![0; ID3V1_SIZE as usize];
+    file.seek(SeekFrom::Start(len - ID3V1_SIZE))
+        .and_then(|_| file.read_exact(&mut raw))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(raw.starts_with(b"TAG").then_some(raw))
+}
+
+fn read_picture_blob(blobs: &Path, p: &PictureBlob) -> Result<Picture, String> {
+    let bytes =
+        fs::read(blobs.join(&p.blob)).map_err(|e| format!("Missing picture in journal: {}", e))?;
+    Ok(Picture::new_unchecked(
+        PictureType::from_u8(p.picture_type),
+        Some(MimeType::from_str(&p.mime)),
+        p.description.clone(),
+        bytes,
+    ))
+}
+
+/// Put an ID3v1 or APE tag back as it was, or remove it if the file had none.
+fn restore_secondary_tag(
+    blobs: &Path,
+    path: &Path,
+    snapshot: &SecondaryTagSnapshot,
+) -> Result<(), String> {
+    let tag_type = match snapshot.tag_type.as_str() {
+        "ID3v1" => TagType::Id3v1,
+        "APE" => TagType::Ape,
+        other => return Err(format!("Unknown tag type in journal: {}", other)),
+    };
+    tag_type
+        .remove_from_path(path)
+        .map_err(|e| format!("Failed to remove {:?} tag: {}", tag_type, e))?;
+    if !snapshot.present {
+        return Ok(());
+    }
+
+    if let Some(blob) = &snapshot.blob {
+        let raw =
+            fs::read(blobs.join(blob)).map_err(|e| format!("Missing tag in journal: {}", e))?;
+        return fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&raw))
+            .map_err(|e| format!("Failed to restore {:?} tag: {}", tag_type, e));
+    }
+    let mut tag = Tag::new(tag_type);
+    for (key, value) in &snapshot.items {
+        tag.push(TagItem::new(
+            ItemKey::from_key(tag_type, key),
+            ItemValue::Text(value.clone()),
+        ));
+    }
+    for pic in &snapshot.pictures {
+        tag.push_picture(read_picture_blob(blobs, pic)?);
+    }
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| format!("Failed to restore {:?} tag: {}", tag_type, e))
+}
+
+fn restore_snapshot(blobs: &Path, snapshot: &TagSnapshot) -> Result<(), String> {
+    let path = Path::new(&snapshot.path);
+    write_tags_to_path(path, &snapshot.tags, &TagWriteOptions::default())?;
+    for secondary in &snapshot.secondary_tags {
+        restore_secondary_tag(blobs, path, secondary)?;
+    }
+
+    if let Some(pictures) = &snapshot.pictures {
+        let pictures = pictures
+            .iter()
+            .map(|p| read_picture_blob(blobs, p))
+            .collect::<Result<Vec<_>, String>>()?;
+        return write_pictures(path, pictures, &TagWriteOptions::default());
+    }
+
+    let cover = match &snapshot.cover_blob {
+        Some(hash) => {
+            let bytes = fs::read(blobs.join(hash))
+                .map_err(|e| format!("Missing cover art in journal: {}", e))?;
+            Some((snapshot.cover_mime.clone(), bytes))
+        }
+        None => None,
+    };
+    set_front_cover(path, cover, &TagWriteOptions::default())
+}
+
+#[tauri::command]
+pub async fn list_transactions(
+    app: AppHandle,
+    kind: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<TransactionSummary>, String> {
+    let dir = journal_dir(&app)?;
+    let mut all = read_all_transactions(&dir);
+
+    // Newest first
+    all.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(all
+        .iter()
+        .filter(|t| match &kind {
+            Some(k) => &t.kind == k,
+            None => true,
+        })
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|t| t.summary())
+        .collect())
+}
+
+/// Move the files of `moves` back, last move first. If one fails, the files
+/// already moved back are moved forward again so that the undo can be retried.
+fn revert_moves(moves: &[FileMove]) -> Result<(), String> {
+    for (i, mv) in moves.iter().enumerate().rev() {
+        let result = Path::new(&mv.from)
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::rename(&mv.to, &mv.from));
+        if let Err(e) = result {
+            redo_moves(&moves[i + 1..]);
+            return Err(format!("Failed to move {} back: {}", mv.to, e));
+        }
+    }
+    Ok(())
+}
+
+/// Put files moved back by `revert_moves` where the transaction had moved them.
+fn redo_moves(reverted: &[FileMove]) {
+    for mv in reverted {
+        let _ = fs::rename(&mv.from, &mv.to);
+    }
+}
+
+/// Undo a transaction: move files back, restore playlists and tags. Of
+/// `options` only `preserve_times` applies; the tags are restored in the
+/// formats and versions they had.
+#[tauri::command]
+pub async fn undo_transaction(
+    app: AppHandle,
+    id: String,
+    options: Option<TagWriteOptions>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let dir = journal_dir(&app)?;
+    let mut tx = load_transaction(&dir, &id)?;
+    if tx.undone {
+        return Err(format!("Transaction {} has already been undone", id));
+    }
+
+    // Refuse to clobber playlists that were edited after the transaction
+    for change in &tx.playlist_changes {
+        let current = fs::read_to_string(&change.path).unwrap_or_default();
+        if current != change.after {
+            return Err(format!(
+                "Playlist was modified after this operation: {}",
+                change.path
+            ));
+        }
+    }
+
+    // Check every move can be reverted before touching anything
+    for mv in &tx.file_moves {
+        if !Path::new(&mv.to).exists() {
+            return Err(format!("File no longer exists: {}", mv.to));
+        }
+        if Path::new(&mv.from).exists() {
+            return Err(format!("Original location is occupied: {}", mv.from));
+        }
+    }
+
+    revert_moves(&tx.file_moves)?;
+
+    let restored: Vec<(String, String)> = tx
+        .playlist_changes
+        .iter()
+        .map(|c| (c.path.clone(), c.before.clone()))
+        .collect();
+    if let Err(e) = write_playlists_atomically(&restored) {
+        redo_moves(&tx.file_moves);
+        return Err(e);
+    }
+
+    // Restore every file we can and report the ones that failed
+    let blobs = blob_dir(&dir)?;
+    let mut edited = Vec::new();
+    let mut errors = Vec::new();
+    // Newest first, so that a file written twice ends up with its oldest snapshot
+    for s in tx.tag_snapshots.iter().rev() {
+        let path = Path::new(&s.path);
+        let times = SavedTimes::read(path);
+        edited.push((s.path.clone(), modified_millis(path)));
+        let mut result = restore_snapshot(&blobs, s);
+        if let (true, Some(times)) = (options.preserve_times, &times) {
+            result = result.and(times.restore(path));
+        }
+        if let Err(e) = result {
+            errors.push(format!("{}: {}", s.path, e));
+        }
+    }
+    let _ = record_tag_edits(&app, &edited);
+    if !errors.is_empty() {
+        return Err(format!("Failed to restore tags: {}", errors.join(", ")));
+    }
+
+    tx.undone = true;
+    save_transaction(&app, &tx)
+}
@@ -0,0 +1,370 @@
+use crate::commands::fs::{read_audio_metadata, read_tags_for_edit};
+use crate::commands::jobs::BackgroundJobs;
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::write_options::TagWriteOptions;
+use ebur128::{EbuR128, Mode};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// ReplayGain 2.0 reference loudness in LUFS
+const REFERENCE_LUFS: f64 = -18.0;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct LoudnessResult {
+    pub path: String,
+    /// Integrated loudness of the track in LUFS
+    #[serde(rename = "integratedLufs")]
+    pub integrated_lufs: f64,
+    /// Linear true peak of the track (1.0 = full scale)
+    #[serde(rename = "truePeak")]
+    pub true_peak: f64,
+    #[serde(rename = "trackGain")]
+    pub track_gain: String,
+    #[serde(rename = "trackPeak")]
+    pub track_peak: String,
+    #[serde(rename = "albumGain")]
+    pub album_gain: String,
+    #[serde(rename = "albumPeak")]
+    pub album_peak: String,
+    /// Whether the values were written into the file's tags
+    pub written: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LoudnessProgress {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LoudnessComplete {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    pub cancelled: bool,
+    pub results: Vec<LoudnessResult>,
+}
+
+struct TrackMeasurement {
+    meter: EbuR128,
+    true_peak: f64,
+}
+
+/// Decode a file and feed every sample into an EBU R128 meter.
+fn measure_track(path: &Path, cancel: &AtomicBool) -> Result<TrackMeasurement, String> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+    use symphonia::core::errors::Error;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unsupported audio format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("No audio track found")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported codec: {}", e))?;
+
+    let mut meter: Option<EbuR128> = None;
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame is skipped, like players do
+            Err(Error::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        if meter.is_none() {
+            meter = Some(
+                EbuR128::new(
+                    spec.channels.count() as u32,
+                    spec.rate,
+                    Mode::I | Mode::TRUE_PEAK | Mode::HISTOGRAM,
+                )
+                .map_err(|e| format!("Failed to start loudness meter: {}", e))?,
+            );
+        }
+        let too_small = match buffer.as_ref() {
+            Some(b) => b.capacity() < decoded.capacity(),
+            None => true,
+        };
+        if too_small {
+            buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+
+        if let (Some(meter), Some(buffer)) = (meter.as_mut(), buffer.as_mut()) {
+            buffer.copy_interleaved_ref(decoded);
+            meter
+                .add_frames_f32(buffer.samples())
+                .map_err(|e| format!("Failed to measure loudness: {}", e))?;
+        }
+    }
+
+    let meter = meter.ok_or("File contains no audio")?;
+    let true_peak = (0..meter.channels())
+        .filter_map(|ch| meter.true_peak(ch).ok())
+        .fold(0.0, f64::max);
+
+    Ok(TrackMeasurement { meter, true_peak })
+}
+
+fn format_gain(loudness: f64) -> String {
+    format!("{:.2} dB", REFERENCE_LUFS - loudness)
+}
+
+fn format_peak(peak: f64) -> String {
+    format!("{:.6}", peak)
+}
+
+/// Key of the album a track belongs to for the album gain.
+fn group_key(path: &Path, group_by: &str) -> String {
+    let directory = || {
+        path.parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    match group_by {
+        "none" => path.to_string_lossy().to_string(),
+        "directory" => directory(),
+        _ => {
+            // "album": album artist (or artist) + album; untagged files fall back to their folder
+            let meta = read_audio_metadata(path, false);
+            if meta.album.is_empty() {
+                directory()
+            } else {
+                let artist = if meta.album_artist.is_empty() {
+                    meta.artist
+                } else {
+                    meta.album_artist
+                };
+                format!("{}\u{0}{}", artist, meta.album)
+            }
+        }
+    }
+}
+
+fn write_replay_gain(
+    recorder: &mut TagEditRecorder,
+    path: &Path,
+    result: &LoudnessResult,
+) -> Result<(), String> {
+    let mut tags = read_tags_for_edit(path, false);
+    tags.replay_gain_track_gain = Some(result.track_gain.clone());
+    tags.replay_gain_track_peak = Some(result.track_peak.clone());
+    tags.replay_gain_album_gain = Some(result.album_gain.clone());
+    tags.replay_gain_album_peak = Some(result.album_peak.clone());
+    recorder.write(path, &tags)
+}
+
+fn run_analysis(
+    app: &AppHandle,
+    job_id: &str,
+    cancel: &AtomicBool,
+    paths: Vec<String>,
+    group_by: &str,
+    write: Option<TagWriteOptions>,
+) -> LoudnessComplete {
+    // A path given twice would be measured, counted in the album gain and
+    // written twice
+    let mut seen = HashSet::new();
+    let paths: Vec<String> = paths
+        .into_iter()
+        .filter(|path| seen.insert(path.clone()))
+        .collect();
+    let total = paths.len();
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for path in paths {
+        groups
+            .entry(group_key(Path::new(&path), group_by))
+            .or_default()
+            .push(path);
+    }
+
+    // Without a journal nothing is written; the measurements are still reported
+    let (mut recorder, journal_error) = match write {
+        Some(options) => match TagEditRecorder::begin(app, "ReplayGain analysis") {
+            Ok(recorder) => (Some(recorder.with_write_options(options)), None),
+            Err(e) => (None, Some(format!("Not written: {}", e))),
+        },
+        None => (None, None),
+    };
+    let mut results = Vec::with_capacity(total);
+    let mut current = 0;
+
+    'groups: for group in groups.into_values() {
+        let mut album: Vec<(LoudnessResult, Option<TrackMeasurement>)> = Vec::new();
+
+        for path in group {
+            if cancel.load(Ordering::Relaxed) {
+                break 'groups;
+            }
+            current += 1;
+            let _ = app.emit(
+                "loudness-progress",
+                LoudnessProgress {
+                    job_id: job_id.to_string(),
+                    current,
+                    total,
+                    path: path.clone(),
+                },
+            );
+
+            let mut result = LoudnessResult {
+                path: path.clone(),
+                ..Default::default()
+            };
+            let measurement = match measure_track(Path::new(&path), cancel).and_then(|m| {
+                let lufs = m
+                    .meter
+                    .loudness_global()
+                    .map_err(|e| format!("Failed to measure loudness: {}", e))?;
+                // A silent track has a loudness of -inf, which no gain can correct
+                if !lufs.is_finite() {
+                    return Err("Track is silent, its loudness cannot be measured".to_string());
+                }
+                Ok((m, lufs))
+            }) {
+                Ok((m, lufs)) => {
+                    result.integrated_lufs = lufs;
+                    result.true_peak = m.true_peak;
+                    result.track_gain = format_gain(lufs);
+                    result.track_peak = format_peak(m.true_peak);
+                    Some(m)
+                }
+                Err(e) => {
+                    result.error = Some(e);
+                    None
+                }
+            };
+            album.push((result, measurement));
+        }
+
+        // A partly measured group would get a wrong album gain
+        if cancel.load(Ordering::Relaxed) {
+            results.extend(album.into_iter().map(|(result, _)| result));
+            break 'groups;
+        }
+
+        // Album values are computed over every track of the group that could be measured
+        let meters: Vec<&EbuR128> = album
+            .iter()
+            .filter_map(|(_, m)| m.as_ref().map(|m| &m.meter))
+            .collect();
+        let album_lufs = EbuR128::loudness_global_multiple(meters.into_iter())
+            .ok()
+            .filter(|lufs| lufs.is_finite());
+        let album_peak = album
+            .iter()
+            .filter_map(|(_, m)| m.as_ref().map(|m| m.true_peak))
+            .fold(0.0, f64::max);
+
+        for (mut result, measurement) in album {
+            if measurement.is_some() {
+                if let Some(lufs) = album_lufs {
+                    result.album_gain = format_gain(lufs);
+                    result.album_peak = format_peak(album_peak);
+                }
+                if let Some(recorder) = recorder.as_mut() {
+                    match write_replay_gain(recorder, Path::new(&result.path), &result) {
+                        Ok(()) => result.written = true,
+                        Err(e) => result.error = Some(e),
+                    }
+                } else if let Some(e) = &journal_error {
+                    result.error = Some(e.clone());
+                }
+            }
+            results.push(result);
+        }
+    }
+
+    // Files written before a cancellation stay in the journal so they can be undone
+    if let Some(recorder) = recorder {
+        if let Err(e) = recorder.finish() {
+            for result in results.iter_mut().filter(|r| r.written) {
+                result.error = Some(e.clone());
+            }
+        }
+    }
+
+    LoudnessComplete {
+        job_id: job_id.to_string(),
+        cancelled: cancel.load(Ordering::Relaxed),
+        results,
+    }
+}
+
+/// Start measuring EBU R128 loudness and true peak of `paths` in the background.
+/// `group_by` ("album" | "directory" | "none") decides which tracks share an album
+/// gain. Progress is reported through `loudness-progress` and the results through
+/// `loudness-complete`; the returned job id can be passed to `cancel_job`.
+/// With `write` set the values are written per `options`, as in `write_audio_tags`.
+#[tauri::command]
+pub async fn start_loudness_analysis(
+    app: AppHandle,
+    jobs: State<'_, BackgroundJobs>,
+    paths: Vec<String>,
+    group_by: Option<String>,
+    write: bool,
+    options: Option<TagWriteOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let write = write.then_some(options);
+    let (job_id, cancel) = jobs.start("loudness");
+    let group_by = group_by.unwrap_or_else(|| "album".to_string());
+
+    let id = job_id.clone();
+    std::thread::spawn(move || {
+        let complete = run_analysis(&app, &id, &cancel, paths, &group_by, write);
+        app.state::<BackgroundJobs>().finish(&id);
+        let _ = app.emit("loudness-complete", complete);
+    });
+
+    Ok(job_id)
+}
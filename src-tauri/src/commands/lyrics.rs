@@ -0,0 +1,265 @@
+use crate::commands::fs::read_tags_for_edit;
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::write_options::TagWriteOptions;
+use lofty::id3::v2::{
+    BinaryFrame, Frame, FrameFlags, FrameId, Id3v2Tag, SyncTextContentType, SynchronizedTextFrame,
+    TimestampFormat,
+};
+use lofty::TextEncoding;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LyricLine {
+    /// Start of the line in milliseconds from the beginning of the track
+    #[serde(rename = "timeMs")]
+    pub time_ms: i64,
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncedLyrics {
+    /// "embedded" | "sidecar" | "none"
+    pub source: String,
+    pub lines: Vec<LyricLine>,
+}
+
+/// `mm:ss`, `mm:ss.xx`, `mm:ss.xxx` or `mm:ss:xx` to milliseconds.
+fn parse_timestamp(stamp: &str) -> Option<i64> {
+    let (minutes, rest) = stamp.split_once(':')?;
+    let (seconds, fraction) = match rest.split_once(['.', ':']) {
+        Some((s, f)) => (s, f),
+        None => (rest, ""),
+    };
+    if !minutes.chars().all(|c| c.is_ascii_digit())
+        || !seconds.chars().all(|c| c.is_ascii_digit())
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+        || fraction.len() > 3
+    {
+        return None;
+    }
+    let minutes: i64 = minutes.parse().ok()?;
+    let seconds: i64 = seconds.parse().ok()?;
+    let millis = match fraction.len() {
+        0 => 0,
+        len => fraction.parse::<i64>().ok()? * 10_i64.pow(3 - len as u32),
+    };
+    Some((minutes * 60 + seconds) * 1000 + millis)
+}
+
+/// Parse LRC text. Lines may carry several timestamps; metadata tags other than
+/// `[offset:]` are ignored. Returns an empty list for text that is not LRC.
+pub(crate) fn parse_lrc(text: &str) -> Vec<LyricLine> {
+    let mut offset = 0;
+    let mut lines = Vec::new();
+
+    for raw in text.lines() {
+        let mut rest = raw.trim_start_matches('\u{feff}').trim();
+        let mut times = Vec::new();
+        while let Some((stamp, after)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            if let Some(ms) = parse_timestamp(stamp.trim()) {
+                times.push(ms);
+            } else if let Some(value) = stamp.strip_prefix("offset:") {
+                offset = value.trim().parse().unwrap_or(0);
+            } else {
+                break;
+            }
+            rest = after;
+        }
+        for time in times {
+            lines.push(LyricLine {
+                time_ms: time,
+                text: rest.trim().to_string(),
+            });
+        }
+    }
+
+    // A positive offset makes the lyrics appear sooner
+    for line in &mut lines {
+        line.time_ms = (line.time_ms - offset).max(0);
+    }
+    lines.sort_by_key(|l| l.time_ms);
+    lines
+}
+
+pub(crate) fn render_lrc(lines: &[LyricLine]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let ms = line.time_ms.max(0);
+            format!(
+                "[{:02}:{:02}.{:02}]{}\n",
+                ms / 60_000,
+                ms / 1000 % 60,
+                ms % 1000 / 10,
+                line.text
+            )
+        })
+        .collect()
+}
+
+fn sylt_id() -> FrameId<'static> {
+    FrameId::Valid(Cow::Borrowed("SYLT"))
+}
+
+/// Lines of the first millisecond-based SYLT frame. lofty keeps SYLT as a
+/// binary frame, so it is decoded here.
+pub(crate) fn from_id3v2(tag: &Id3v2Tag) -> Vec<LyricLine> {
+    for frame in tag {
+        let Frame::Binary(binary) = frame else {
+            continue;
+        };
+        if frame.id_str() != "SYLT" {
+            continue;
+        }
+        let Ok(sylt) = SynchronizedTextFrame::parse(&binary.data, FrameFlags::default()) else {
+            continue;
+        };
+        // MPEG frame timestamps cannot be converted without the frame rate
+        if sylt.timestamp_format != TimestampFormat::MS {
+            continue;
+        }
+        return sylt
+            .content
+            .iter()
+            .map(|(time, text)| LyricLine {
+                time_ms: i64::from(*time),
+                text: text.trim_matches(['\r', '\n']).to_string(),
+            })
+            .collect();
+    }
+    Vec::new()
+}
+
+/// Replace the SYLT frames of an ID3v2 tag with `lines` (none if empty).
+pub(crate) fn apply_to_id3v2(tag: &mut Id3v2Tag, lines: &[LyricLine]) {
+    tag.remove(&sylt_id()).for_each(drop);
+    if lines.is_empty() {
+        return;
+    }
+
+    let sylt = SynchronizedTextFrame::new(
+        TextEncoding::UTF16,
+        *b"XXX",
+        TimestampFormat::MS,
+        SyncTextContentType::Lyrics,
+        None,
+        lines
+            .iter()
+            .map(|l| (l.time_ms.max(0) as u32, l.text.clone()))
+            .collect(),
+    );
+    if let Ok(bytes) = sylt.as_bytes() {
+        tag.insert(Frame::Binary(BinaryFrame::new(sylt_id(), bytes)));
+    }
+}
+
+/// The `.lrc` file next to an audio file.
+fn sidecar_path(path: &Path) -> PathBuf {
+    path.with_extension("lrc")
+}
+
+fn read_lrc_file(path: &Path) -> Result<Vec<LyricLine>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read LRC file: {}", e))?;
+    let lines = parse_lrc(&String::from_utf8_lossy(&bytes));
+    if lines.is_empty() {
+        return Err(format!("No timed lines in {}", path.display()));
+    }
+    Ok(lines)
+}
+
+/// Synced lyrics of a track: embedded ones (SYLT, or LRC text in the lyrics
+/// field) take precedence over a sidecar `.lrc` file.
+#[tauri::command]
+pub async fn read_synced_lyrics(path: String) -> Result<SyncedLyrics, String> {
+    let file_path = Path::new(&path);
+    if !file_path.exists() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    let embedded = read_tags_for_edit(file_path, false)
+        .synced_lyrics
+        .unwrap_or_default();
+    if !embedded.is_empty() {
+        return Ok(SyncedLyrics {
+            source: "embedded".to_string(),
+            lines: embedded,
+        });
+    }
+
+    let sidecar = sidecar_path(file_path);
+    if sidecar.is_file() {
+        if let Ok(lines) = read_lrc_file(&sidecar) {
+            return Ok(SyncedLyrics {
+                source: "sidecar".to_string(),
+                lines,
+            });
+        }
+    }
+
+    Ok(SyncedLyrics {
+        source: "none".to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Embed the lines of an `.lrc` file (the sidecar file by default) into the track.
+/// `options` applies as in `write_audio_tags`.
+#[tauri::command]
+pub async fn import_lrc(
+    app: AppHandle,
+    path: String,
+    lrc_path: Option<String>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<LyricLine>, String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let file_path = Path::new(&path);
+    let lrc_path = lrc_path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| sidecar_path(file_path));
+    let lines = read_lrc_file(&lrc_path)?;
+
+    let mut tags = read_tags_for_edit(file_path, false);
+    tags.synced_lyrics = Some(lines.clone());
+
+    let mut recorder = TagEditRecorder::begin(&app, "Import lyrics")?.with_write_options(options);
+    let result = recorder.write(file_path, &tags);
+    recorder.finish()?;
+    result.map(|_| lines)
+}
+
+/// Write the track's embedded synced lyrics to an `.lrc` file (the sidecar file
+/// by default) and return its path. An existing file is only replaced with
+/// `overwrite` set.
+#[tauri::command]
+pub async fn export_lrc(
+    path: String,
+    lrc_path: Option<String>,
+    overwrite: bool,
+) -> Result<String, String> {
+    let file_path = Path::new(&path);
+    if !file_path.exists() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    let lines = read_tags_for_edit(file_path, false)
+        .synced_lyrics
+        .unwrap_or_default();
+    if lines.is_empty() {
+        return Err("The track has no synced lyrics".to_string());
+    }
+
+    let lrc_path = lrc_path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| sidecar_path(file_path));
+    if lrc_path.exists() && !overwrite {
+        return Err(format!("File already exists: {}", lrc_path.display()));
+    }
+    fs::write(&lrc_path, render_lrc(&lines))
+        .map_err(|e| format!("Failed to write LRC file: {}", e))?;
+    Ok(lrc_path.to_string_lossy().to_string())
+}
@@ -43,7 +43,12 @@ pub async fn list_playlists(
     root: String,
     playlist_dir: Option<String>,
 ) -> Result<Vec<String>, String> {
-    let root_path = Path::new(&root);
+    Ok(collect_playlists(&root, playlist_dir.as_deref()))
+}
+
+/// Collect every playlist file Lochord manages for the given music root.
+pub(crate) fn collect_playlists(root: &str, playlist_dir: Option<&str>) -> Vec<String> {
+    let root_path = Path::new(root);
 
     let mut playlists = Vec::new();
 
@@ -52,7 +57,7 @@ pub async fn list_playlists(
 
     // If a custom playlistDir is set (and different from musicRoot),
     // also scan that directory for all supported extensions
-    if let Some(ref pd) = playlist_dir {
+    if let Some(pd) = playlist_dir {
         let pd_path = Path::new(pd);
         // Only scan separately if it's not already under musicRoot
         // (if it is inside musicRoot, m3u/m3u8 are already found above;
@@ -67,7 +72,7 @@ pub async fn list_playlists(
     // Deduplicate (same file may be found twice if playlistDir is inside root)
     playlists.sort();
     playlists.dedup();
-    Ok(playlists)
+    playlists
 }
 
 #[tauri::command]
@@ -242,7 +247,7 @@ fn csv_escape(s: &str) -> String {
     }
 }
 
-fn compute_relative_path(from_dir: &Path, to_file: &Path) -> String {
+pub(crate) fn compute_relative_path(from_dir: &Path, to_file: &Path) -> String {
     // Try to make a relative path from from_dir to to_file
     let from_parts: Vec<_> = from_dir.components().collect();
     let to_parts: Vec<_> = to_file.components().collect();
@@ -324,25 +329,13 @@ fn parse_m3u8(content: &str, playlist_dir: &Path) -> Vec<Track> {
             tracks.push(Track {
                 title,
                 artist,
-                album_artist: String::new(),
                 album,
                 genre,
                 year,
-                track_number: 0,
-                total_tracks: 0,
-                disc_number: 0,
-                total_discs: 0,
-                composer: String::new(),
-                comment: String::new(),
-                lyrics: String::new(),
-                bpm: 0,
-                copyright: String::new(),
-                publisher: String::new(),
-                isrc: String::new(),
-                cover_art: String::new(),
                 duration,
                 relative_path,
                 absolute_path,
+                ..Default::default()
             });
 
             extinf_duration = 0;
@@ -427,25 +420,13 @@ fn resolve_path_to_track(path_line: &str, playlist_dir: &Path) -> Option<Track>
     Some(Track {
         title,
         artist,
-        album_artist: String::new(),
         album,
         genre,
         year,
-        track_number: 0,
-        total_tracks: 0,
-        disc_number: 0,
-        total_discs: 0,
-        composer: String::new(),
-        comment: String::new(),
-        lyrics: String::new(),
-        bpm: 0,
-        copyright: String::new(),
-        publisher: String::new(),
-        isrc: String::new(),
-        cover_art: String::new(),
         duration,
         relative_path: rel_path,
         absolute_path,
+        ..Default::default()
     })
 }
 
@@ -519,3 +500,172 @@ fn csv_split(line: &str) -> Vec<String> {
     fields.push(current);
     fields
 }
+
+/// A playlist file kept as raw lines so that its path entries can be rewritten
+/// in place without touching EXTINF lines, comments or CSV metadata columns.
+pub(crate) struct PlaylistDocument {
+    format: String,
+    lines: Vec<String>,
+    /// "\r\n" or "\n", as the file had it
+    line_ending: &'static str,
+    /// Indices into `lines` of the lines that hold a track path
+    entries: Vec<usize>,
+}
+
+impl PlaylistDocument {
+    pub(crate) fn read(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read playlist: {}", e))?;
+
+        let format = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let line_ending = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+        let mut entries = Vec::new();
+        let mut is_first = true;
+
+        for (i, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            match format.as_str() {
+                "csv" => {
+                    // Skip header row (same rule as parse_csv)
+                    if is_first {
+                        is_first = false;
+                        if trimmed.to_lowercase().starts_with("title") {
+                            continue;
+                        }
+                    }
+                    if csv_split(trimmed).len() >= 4 {
+                        entries.push(i);
+                    }
+                }
+                _ => {
+                    if !trimmed.starts_with('#') {
+                        entries.push(i);
+                    }
+                }
+            }
+        }
+
+        Ok(PlaylistDocument {
+            format,
+            lines,
+            line_ending,
+            entries,
+        })
+    }
+
+    pub(crate) fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The path of the n-th entry exactly as written in the playlist.
+    pub(crate) fn entry_path(&self, n: usize) -> String {
+        let line = self.lines[self.entries[n]].trim();
+        if self.format == "csv" {
+            csv_split(line)[3].trim().trim_matches('"').to_string()
+        } else {
+            line.to_string()
+        }
+    }
+
+    pub(crate) fn set_entry_path(&mut self, n: usize, path: &str) {
+        let index = self.entries[n];
+        if self.format == "csv" {
+            let mut fields = csv_split(self.lines[index].trim());
+            fields[3] = path.to_string();
+            self.lines[index] = fields
+                .iter()
+                .map(|f| csv_escape(f))
+                .collect::<Vec<_>>()
+                .join(",");
+        } else {
+            self.lines[index] = path.to_string();
+        }
+    }
+
+    pub(crate) fn render(&self) -> String {
+        self.lines.join(self.line_ending) + self.line_ending
+    }
+}
+
+/// Resolve a playlist entry against the directory of the playlist that holds it.
+/// Absolute entries are returned unchanged.
+pub(crate) fn resolve_entry_path(entry: &str, playlist_dir: &Path) -> PathBuf {
+    playlist_dir.join(entry.replace('\\', "/"))
+}
+
+/// Write several playlists so that either all of them are replaced or none are.
+/// Every file is first written to a temporary sibling; the originals are then
+/// moved aside one by one and put back if any replacement fails.
+pub(crate) fn write_playlists_atomically(files: &[(String, String)]) -> Result<(), String> {
+    let mut staged: Vec<(PathBuf, &str)> = Vec::new();
+
+    for (path, content) in files {
+        let tmp_path = PathBuf::from(format!("{}.lochord-tmp", path));
+        if let Err(e) = fs::write(&tmp_path, content.as_bytes()) {
+            let _ = fs::remove_file(&tmp_path);
+            for (tmp, _) in &staged {
+                let _ = fs::remove_file(tmp);
+            }
+            return Err(format!("Failed to write playlist {}: {}", path, e));
+        }
+        staged.push((tmp_path, path.as_str()));
+    }
+
+    // (backup of the original if there was one, replaced path)
+    let mut replaced: Vec<(Option<PathBuf>, &str)> = Vec::new();
+    for (i, (tmp, path)) in staged.iter().enumerate() {
+        let backup = PathBuf::from(format!("{}.lochord-bak", path));
+        let result = if Path::new(path).exists() {
+            fs::rename(path, &backup).map(|_| Some(backup))
+        } else {
+            Ok(None)
+        }
+        .and_then(|backup| match fs::rename(tmp, path) {
+            Ok(()) => Ok(backup),
+            Err(e) => {
+                if let Some(backup) = &backup {
+                    let _ = fs::rename(backup, path);
+                }
+                Err(e)
+            }
+        });
+
+        match result {
+            Ok(backup) => replaced.push((backup, path)),
+            Err(e) => {
+                for (backup, path) in replaced.iter().rev() {
+                    match backup {
+                        Some(backup) => {
+                            let _ = fs::rename(backup, path);
+                        }
+                        None => {
+                            let _ = fs::remove_file(path);
+                        }
+                    }
+                }
+                for (tmp, _) in &staged[i..] {
+                    let _ = fs::remove_file(tmp);
+                }
+                return Err(format!("Failed to replace playlist {}: {}", path, e));
+            }
+        }
+    }
+
+    for backup in replaced.into_iter().filter_map(|(backup, _)| backup) {
+        let _ = fs::remove_file(backup);
+    }
+    Ok(())
+}
@@ -1,2 +1,23 @@
+pub mod artwork;
+pub mod audio_properties;
+pub mod cleanup;
+pub mod custom_fields;
+pub mod file_times;
+pub mod filename_tags;
+pub mod folder_art;
 pub mod fs;
+pub mod id3v2;
+pub mod jobs;
+pub mod journal;
+pub mod loudness;
+pub mod lyrics;
 pub mod m3u;
+pub mod mojibake;
+pub mod organize;
+pub mod pictures;
+pub mod ratings;
+pub mod relink;
+pub mod tag_batch;
+pub mod tag_diff;
+pub mod thumbnails;
+pub mod write_options;
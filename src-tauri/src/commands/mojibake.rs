@@ -0,0 +1,367 @@
+use crate::commands::fs::{read_tags_for_edit, AudioTags, TagWriteResult, MULTI_VALUE_SEPARATOR};
+use crate::commands::id3v2;
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::tag_batch::TagWriteProgress;
+use crate::commands::tag_diff::{diff_against_file, FieldChange};
+use crate::commands::write_options::TagWriteOptions;
+use encoding_rs::{Encoding, SHIFT_JIS};
+use lofty::config::ParseOptions;
+use lofty::id3::v2::Frame;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::TagType;
+use lofty::TextEncoding;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MojibakePreview {
+    pub path: String,
+    /// Fields whose text decodes cleanly, with the repaired value
+    pub changes: Vec<FieldChange>,
+    /// Fields that look misencoded but are not valid in the chosen encoding
+    pub undecodable: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// The encoding named by `label`. Text in a single-byte encoding cannot be
+/// told apart from real Latin-1 text, so only multi-byte ones are accepted.
+fn resolve_encoding(label: Option<&str>) -> Result<&'static Encoding, String> {
+    let encoding = match label {
+        None => SHIFT_JIS,
+        Some(label) => Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| format!("Unknown encoding: {}", label))?,
+    };
+    if encoding.is_single_byte() || !encoding.is_ascii_compatible() {
+        return Err(format!(
+            "{} cannot be detected in Latin-1 text, choose a multi-byte encoding",
+            encoding.name()
+        ));
+    }
+    Ok(encoding)
+}
+
+/// The bytes behind a string that was decoded as Latin-1, if it could have
+/// been: every character fits in a byte and at least one is not ASCII.
+fn latin1_bytes(text: &str) -> Option<Vec<u8>> {
+    if text.is_ascii() {
+        return None;
+    }
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).ok())
+        .collect()
+}
+
+/// Whether `text` looks like legacy-encoded bytes read as Latin-1.
+fn is_suspicious(text: &str) -> bool {
+    latin1_bytes(text).is_some()
+}
+
+/// Characters that real tag text does not contain: controls other than
+/// whitespace, and the private use area that vendor-specific lead bytes (e.g.
+/// Latin-1 "ö" in Shift_JIS) decode to.
+fn is_implausible(c: char) -> bool {
+    (c.is_control() && !c.is_whitespace()) || ('\u{E000}'..='\u{F8FF}').contains(&c)
+}
+
+/// The bytes of `c` in `encoding`.
+fn encoded(c: char, encoding: &'static Encoding) -> Vec<u8> {
+    let mut buf = [0; 4];
+    let (bytes, _, _) = encoding.encode(c.encode_utf8(&mut buf));
+    bytes.into_owned()
+}
+
+/// Kana and hangul, which legacy Japanese and Korean text nearly always has.
+fn is_kana_or_hangul(c: char) -> bool {
+    ('\u{3040}'..='\u{30FF}').contains(&c) || ('\u{AC00}'..='\u{D7A3}').contains(&c)
+}
+
+/// `text` re-decoded from the bytes it was read from, if the whole of it is
+/// valid multi-byte text in `encoding`. A non-ASCII byte that decodes on its
+/// own (e.g. the half-width katakana of Shift_JIS) is as likely to be real
+/// Latin-1 text such as "Ça", so it rejects the string. An accented letter
+/// and the ASCII letter after it can also form a valid pair ("él" in "Amélie"
+/// is a Shift_JIS kanji), so the text must also have kana, hangul or a
+/// character made of non-ASCII bytes only, which Latin-1 names rarely give.
+pub(crate) fn repair_text(text: &str, encoding: &'static Encoding) -> Option<String> {
+    let bytes = latin1_bytes(text)?;
+    let decoded = encoding.decode_without_bom_handling_and_without_replacement(&bytes)?;
+    let multi_byte = decoded
+        .chars()
+        .all(|c| c.is_ascii() || encoded(c, encoding).len() > 1);
+    let evidence = decoded.chars().any(|c| {
+        is_kana_or_hangul(c) || (!c.is_ascii() && encoded(c, encoding).iter().all(|b| *b >= 0x80))
+    });
+    let clean = !decoded.chars().any(is_implausible);
+    (multi_byte && evidence && clean && decoded != text).then(|| decoded.into_owned())
+}
+
+/// Text the file stores as Latin-1: ID3v2 frames whose encoding byte says so,
+/// or the whole ID3v1 tag. Only such text can be legacy bytes read as
+/// Latin-1; Unicode frames and other tag formats are never repaired.
+fn latin1_texts(path: &Path) -> Result<HashSet<String>, String> {
+    let tagged = Probe::open(path)
+        .map_err(|e| format!("Failed to open file: {}", e))?
+        .options(ParseOptions::new().read_properties(false))
+        .read()
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let mut texts = HashSet::new();
+    // The tag `read_audio_metadata` reads from
+    let Some(tag) = tagged.primary_tag().or_else(|| tagged.first_tag()) else {
+        return Ok(texts);
+    };
+    match tag.tag_type() {
+        TagType::Id3v1 => {
+            texts.extend(
+                tag.items()
+                    .filter_map(|item| item.value().text())
+                    .map(str::to_string),
+            );
+        }
+        TagType::Id3v2 => {
+            let Some(id3) = id3v2::read_tag(path, tagged.file_type())? else {
+                return Ok(texts);
+            };
+            for frame in &id3 {
+                let text: &str = match frame {
+                    Frame::Text(f) if f.encoding == TextEncoding::Latin1 => &*f.value,
+                    Frame::UserText(f) if f.encoding == TextEncoding::Latin1 => &*f.content,
+                    Frame::Comment(f) if f.encoding == TextEncoding::Latin1 => &*f.content,
+                    Frame::UnsynchronizedText(f) if f.encoding == TextEncoding::Latin1 => {
+                        &*f.content
+                    }
+                    _ => continue,
+                };
+                // ID3v2.4 separates the values of a multi-value frame with NUL
+                texts.extend(text.split('\0').map(str::to_string));
+                texts.insert(text.to_string());
+            }
+        }
+        _ => {}
+    }
+    Ok(texts)
+}
+
+/// Whether `text` was read from Latin-1 text, either as a whole or, for the
+/// display string of a multi-value field, value by value.
+fn is_latin1(text: &str, latin1: &HashSet<String>) -> bool {
+    latin1.contains(text)
+        || text
+            .split(MULTI_VALUE_SEPARATOR)
+            .all(|value| latin1.contains(value))
+}
+
+/// Repair every string in `value` that was read from Latin-1 text; object keys
+/// (custom field names) are left alone.
+fn repair_value(
+    value: &mut Value,
+    encoding: &'static Encoding,
+    latin1: &HashSet<String>,
+    undecodable: &mut bool,
+) {
+    match value {
+        Value::String(text) if is_latin1(text, latin1) => match repair_text(text, encoding) {
+            Some(fixed) => *text = fixed,
+            None => *undecodable |= is_suspicious(text),
+        },
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| repair_value(item, encoding, latin1, undecodable)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| repair_value(field, encoding, latin1, undecodable)),
+        _ => {}
+    }
+}
+
+/// The file's tags with misencoded text re-decoded from `encoding`, and the
+/// fields that look misencoded but could not be repaired. With `fields` set,
+/// only those fields are repaired.
+fn repaired_tags(
+    path: &Path,
+    encoding: &'static Encoding,
+    fields: Option<&[String]>,
+) -> Result<(AudioTags, Vec<String>), String> {
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    // Cover art is not loaded, so the pictures stay as-is
+    let tags = read_tags_for_edit(path, false);
+    let latin1 = latin1_texts(path)?;
+    let mut value = serde_json::to_value(&tags).map_err(|e| e.to_string())?;
+    let tag_fields = value
+        .as_object_mut()
+        .ok_or("AudioTags must serialize to an object")?;
+
+    let mut undecodable = Vec::new();
+    for (field, field_value) in tag_fields.iter_mut() {
+        if matches!(
+            field.as_str(),
+            "coverArt" | "coverArtSource" | "removePictures"
+        ) {
+            continue;
+        }
+        if fields.is_some_and(|fields| !fields.contains(field)) {
+            continue;
+        }
+        let mut failed = false;
+        repair_value(field_value, encoding, &latin1, &mut failed);
+        if failed {
+            undecodable.push(field.clone());
+        }
+    }
+
+    let repaired = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok((repaired, undecodable))
+}
+
+/// Find tag text that was stored in a legacy encoding but read as Latin-1 and
+/// show what re-decoding it from `encoding` (default Shift_JIS) would write.
+#[tauri::command]
+pub async fn preview_mojibake_repair(
+    paths: Vec<String>,
+    encoding: Option<String>,
+) -> Result<Vec<MojibakePreview>, String> {
+    let encoding = resolve_encoding(encoding.as_deref())?;
+
+    Ok(paths
+        .iter()
+        .map(|path| {
+            let file_path = Path::new(path);
+            let result =
+                repaired_tags(file_path, encoding, None).and_then(|(tags, undecodable)| {
+                    Ok((diff_against_file(file_path, &tags)?, undecodable))
+                });
+            match result {
+                Ok((changes, undecodable)) => MojibakePreview {
+                    path: path.clone(),
+                    changes,
+                    undecodable,
+                    error: None,
+                },
+                Err(e) => MojibakePreview {
+                    path: path.clone(),
+                    changes: Vec::new(),
+                    undecodable: Vec::new(),
+                    error: Some(e),
+                },
+            }
+        })
+        .collect())
+}
+
+fn repair_file(
+    recorder: &mut TagEditRecorder,
+    path: &Path,
+    encoding: &'static Encoding,
+    fields: Option<&[String]>,
+) -> Result<(), String> {
+    let (tags, _) = repaired_tags(path, encoding, fields)?;
+    if diff_against_file(path, &tags)?.is_empty() {
+        return Ok(());
+    }
+    recorder.write(path, &tags)
+}
+
+/// Re-decode misencoded tag text from `encoding` (default Shift_JIS) and write
+/// it back as Unicode (UTF-16 in ID3v2.3, UTF-8 in ID3v2.4). `fields` limits the
+/// repair to the fields picked from the preview; all are repaired without it.
+/// ID3v1 cannot hold the repaired text, so pass `id3v1: "remove"` in `options`
+/// to drop its stale copy. Emits `tag-write-progress` after each file.
+#[tauri::command]
+pub async fn repair_mojibake(
+    app: AppHandle,
+    paths: Vec<String>,
+    encoding: Option<String>,
+    fields: Option<Vec<String>>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<TagWriteResult>, String> {
+    let encoding = resolve_encoding(encoding.as_deref())?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let total = paths.len();
+    let mut results = Vec::with_capacity(total);
+    let mut recorder =
+        TagEditRecorder::begin(&app, "Repair text encoding")?.with_write_options(options);
+
+    for (i, path) in paths.iter().enumerate() {
+        let result = repair_file(&mut recorder, Path::new(path), encoding, fields.as_deref());
+        results.push(TagWriteResult::from_result(path, result));
+
+        let _ = app.emit(
+            "tag-write-progress",
+            TagWriteProgress {
+                current: i + 1,
+                total,
+                path: path.clone(),
+            },
+        );
+    }
+
+    recorder.finish_with_results(&mut results);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{EUC_KR, UTF_8};
+
+    /// `text` stored in `encoding` and read back as Latin-1.
+    fn garbled(text: &str, encoding: &'static Encoding) -> String {
+        let (bytes, _, _) = encoding.encode(text);
+        bytes.iter().map(|b| char::from(*b)).collect()
+    }
+
+    #[test]
+    fn repairs_legacy_text() {
+        for text in ["テスト", "ひらがな", "東京", "Live in 東京", "アイ"] {
+            assert_eq!(
+                repair_text(&garbled(text, SHIFT_JIS), SHIFT_JIS).as_deref(),
+                Some(text)
+            );
+        }
+        assert_eq!(
+            repair_text(&garbled("안녕", EUC_KR), EUC_KR).as_deref(),
+            Some("안녕")
+        );
+        assert_eq!(
+            repair_text(&garbled("Café", UTF_8), UTF_8).as_deref(),
+            Some("Café")
+        );
+    }
+
+    #[test]
+    fn keeps_latin1_names() {
+        for text in [
+            "Björk",
+            "Café",
+            "Amélie",
+            "Pokémon",
+            "Ça plane pour moi",
+            "Mötley Crüe",
+            "Hello",
+        ] {
+            assert_eq!(repair_text(text, SHIFT_JIS), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_half_width_kana() {
+        let text = garbled("ｶﾀｶﾅ", SHIFT_JIS);
+        assert!(is_suspicious(&text));
+        assert_eq!(repair_text(&text, SHIFT_JIS), None);
+    }
+
+    #[test]
+    fn rejects_single_byte_encodings() {
+        assert!(resolve_encoding(Some("windows-1252")).is_err());
+        assert!(resolve_encoding(Some("utf-16le")).is_err());
+        assert!(resolve_encoding(None).is_ok());
+    }
+}
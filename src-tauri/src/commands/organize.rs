@@ -0,0 +1,649 @@
+use crate::commands::fs::{read_audio_metadata, AudioMetadata};
+use crate::commands::journal::{save_transaction, FileMove, PlaylistChange, Transaction};
+use crate::commands::m3u::{
+    collect_playlists, compute_relative_path, resolve_entry_path, write_playlists_atomically,
+    PlaylistDocument,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TrackMove {
+    /// Existing file or folder inside the music root
+    pub from: String,
+    /// New location inside the music root (must not exist yet)
+    pub to: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MoveTracksResult {
+    #[serde(rename = "transactionId")]
+    pub transaction_id: String,
+    pub moved: Vec<FileMove>,
+    #[serde(rename = "updatedPlaylists")]
+    pub updated_playlists: Vec<String>,
+    /// Set when the moves succeeded but could not be recorded for undo;
+    /// `transaction_id` is empty then
+    #[serde(rename = "journalError")]
+    pub journal_error: Option<String>,
+}
+
+struct PlannedMove {
+    from: PathBuf,
+    to: PathBuf,
+    /// `from` relative to the music root, with forward slashes
+    from_rel: String,
+    /// `to` relative to the music root, with forward slashes
+    to_rel: String,
+}
+
+/// Lexically normalize a path: drop `.` and resolve `..` without touching the filesystem
+/// (the source of a move no longer exists by the time playlists are rewritten).
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+fn root_relative(path: &Path, root: &Path) -> Option<String> {
+    path.strip_prefix(root)
+        .ok()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .filter(|p| !p.is_empty())
+}
+
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn plan_moves(root: &Path, moves: &[TrackMove]) -> Result<Vec<PlannedMove>, String> {
+    let mut plan: Vec<PlannedMove> = Vec::new();
+
+    for mv in moves {
+        let from = normalize_path(Path::new(&mv.from));
+        let to = normalize_path(Path::new(&mv.to));
+
+        let from_rel = root_relative(&from, root)
+            .ok_or_else(|| format!("Source is outside the music root: {}", mv.from))?;
+        let to_rel = root_relative(&to, root)
+            .ok_or_else(|| format!("Destination is outside the music root: {}", mv.to))?;
+
+        if !from.exists() {
+            return Err(format!("File not found: {}", mv.from));
+        }
+        // A case-only rename on a case-insensitive filesystem reports the target as existing
+        if to.exists() && !is_same_file(&from, &to) {
+            return Err(format!("Destination already exists: {}", mv.to));
+        }
+        if from == to {
+            continue;
+        }
+        if to.starts_with(&from) {
+            return Err(format!("Cannot move a folder into itself: {}", mv.from));
+        }
+        // Chained or nested moves would make both the playlist rewrite and undo ambiguous
+        for other in &plan {
+            if from.starts_with(&other.from)
+                || other.from.starts_with(&from)
+                || to.starts_with(&other.to)
+                || other.to.starts_with(&to)
+                || from.starts_with(&other.to)
+                || to.starts_with(&other.from)
+            {
+                return Err(format!(
+                    "Overlapping moves: {} and {}",
+                    mv.from, other.from_rel
+                ));
+            }
+        }
+
+        plan.push(PlannedMove {
+            from,
+            to,
+            from_rel,
+            to_rel,
+        });
+    }
+
+    Ok(plan)
+}
+
+fn execute_moves(plan: &[PlannedMove]) -> Result<(), String> {
+    for (i, mv) in plan.iter().enumerate() {
+        let result = mv
+            .to
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::rename(&mv.from, &mv.to));
+
+        if let Err(e) = result {
+            rollback_moves(&plan[..i]);
+            return Err(format!("Failed to move {}: {}", mv.from.display(), e));
+        }
+    }
+    Ok(())
+}
+
+fn rollback_moves(done: &[PlannedMove]) {
+    for mv in done.iter().rev() {
+        let _ = fs::rename(&mv.to, &mv.from);
+    }
+}
+
+/// Find `segment` inside `entry` on path-component boundaries, as either the whole
+/// tail of the entry or followed by a `/`.
+fn find_segment(entry: &str, segment: &str) -> Option<usize> {
+    entry
+        .match_indices(segment)
+        .map(|(i, _)| i)
+        .filter(|&i| {
+            let end = i + segment.len();
+            (i == 0 || entry[..i].ends_with('/'))
+                && (end == entry.len() || entry[end..].starts_with('/'))
+        })
+        .last()
+}
+
+/// Prefixes the playlist's "relative-from-root" / "relative-from-prefix" entries put
+/// in front of the root-relative path: none, the configured `path_prefix`, and
+/// whatever the entries that still point to a file under `root` use.
+fn root_prefixes(
+    doc: &PlaylistDocument,
+    playlist_dir: &Path,
+    root: &Path,
+    path_prefix: Option<&str>,
+) -> Vec<String> {
+    let mut prefixes = vec![String::new()];
+    if let Some(prefix) = path_prefix
+        .map(|p| p.replace('\\', "/").trim_end_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+    {
+        prefixes.push(format!("{}/", prefix));
+    }
+
+    for n in 0..doc.entry_count() {
+        let entry = doc.entry_path(n).replace('\\', "/");
+        if resolve_entry_path(&entry, playlist_dir).exists() {
+            continue;
+        }
+        let learned = std::iter::once(0)
+            .chain(entry.match_indices('/').map(|(i, _)| i + 1))
+            .find(|&i| root.join(&entry[i..]).exists())
+            .map(|i| entry[..i].to_string());
+        if let Some(prefix) = learned {
+            if !prefixes.contains(&prefix) {
+                prefixes.push(prefix);
+            }
+        }
+    }
+    prefixes
+}
+
+/// Rewrite a single playlist entry for a move, keeping the path style and separators
+/// the entry was written in. Root-relative entries are only recognized behind one
+/// of `prefixes`. Returns `None` if the entry does not reference the moved file or folder.
+fn relocate_entry(
+    entry: &str,
+    playlist_dir: &Path,
+    prefixes: &[String],
+    mv: &PlannedMove,
+) -> Option<String> {
+    let normalized = entry.replace('\\', "/");
+    let resolved = normalize_path(&resolve_entry_path(&normalized, playlist_dir));
+    let restyle = |path: String| {
+        if entry.contains('\\') {
+            path.replace('/', "\\")
+        } else {
+            path
+        }
+    };
+
+    // "absolute" and "relative" (from the playlist directory) entries
+    if let Ok(rest) = resolved.strip_prefix(&mv.from) {
+        let target = if rest.as_os_str().is_empty() {
+            mv.to.clone()
+        } else {
+            mv.to.join(rest)
+        };
+        return Some(restyle(if Path::new(&normalized).is_absolute() {
+            target.to_string_lossy().replace('\\', "/")
+        } else {
+            compute_relative_path(playlist_dir, &target).replace('\\', "/")
+        }));
+    }
+
+    // "relative-from-root" and "relative-from-prefix" entries do not resolve against
+    // the playlist directory; locate the root-relative part of the source instead
+    if resolved.exists() {
+        return None;
+    }
+    let pos = find_segment(&normalized, &mv.from_rel)?;
+    if !prefixes.iter().any(|prefix| *prefix == normalized[..pos]) {
+        return None;
+    }
+    Some(restyle(format!(
+        "{}{}{}",
+        &normalized[..pos],
+        mv.to_rel,
+        &normalized[pos + mv.from_rel.len()..]
+    )))
+}
+
+fn relocate_playlists(
+    music_root: &str,
+    playlist_dir: Option<&str>,
+    path_prefix: Option<&str>,
+    plan: &[PlannedMove],
+) -> Result<Vec<PlaylistChange>, String> {
+    let root = Path::new(music_root);
+    let mut changes = Vec::new();
+
+    for playlist in collect_playlists(music_root, playlist_dir) {
+        let playlist_path = Path::new(&playlist);
+        let dir = playlist_path.parent().ok_or("Invalid playlist path")?;
+        let before = fs::read_to_string(playlist_path)
+            .map_err(|e| format!("Failed to read playlist: {}", e))?;
+        let mut doc = PlaylistDocument::read(playlist_path)?;
+        let prefixes = root_prefixes(&doc, dir, root, path_prefix);
+
+        let mut changed = false;
+        for n in 0..doc.entry_count() {
+            let entry = doc.entry_path(n);
+            if let Some(rewritten) = plan
+                .iter()
+                .find_map(|mv| relocate_entry(&entry, dir, &prefixes, mv))
+            {
+                doc.set_entry_path(n, &rewritten);
+                changed = true;
+            }
+        }
+
+        if changed {
+            changes.push(PlaylistChange {
+                path: playlist,
+                before,
+                after: doc.render(),
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Move files/folders inside the music root, fix every playlist that references
+/// them and record the whole operation as one undoable transaction. `path_prefix`
+/// is the prefix "relative-from-prefix" playlists are saved with.
+pub(crate) fn perform_moves(
+    app: &AppHandle,
+    music_root: &str,
+    playlist_dir: Option<&str>,
+    path_prefix: Option<&str>,
+    moves: &[TrackMove],
+    label: &str,
+) -> Result<MoveTracksResult, String> {
+    let root = normalize_path(Path::new(music_root));
+    let plan = plan_moves(&root, moves)?;
+
+    execute_moves(&plan)?;
+
+    let changes = match relocate_playlists(music_root, playlist_dir, path_prefix, &plan) {
+        Ok(changes) => changes,
+        Err(e) => {
+            rollback_moves(&plan);
+            return Err(e);
+        }
+    };
+    let staged: Vec<(String, String)> = changes
+        .iter()
+        .map(|c| (c.path.clone(), c.after.clone()))
+        .collect();
+    if let Err(e) = write_playlists_atomically(&staged) {
+        rollback_moves(&plan);
+        return Err(e);
+    }
+
+    let mut tx = Transaction::new("move", label);
+    tx.file_moves = plan
+        .iter()
+        .map(|mv| FileMove {
+            from: mv.from.to_string_lossy().to_string(),
+            to: mv.to.to_string_lossy().to_string(),
+        })
+        .collect();
+    tx.playlist_changes = changes;
+    // The moves are done either way, so a journal failure is reported, not returned
+    let journal_error = save_transaction(app, &tx)
+        .err()
+        .map(|e| format!("Files were moved but the undo journal failed: {}", e));
+
+    Ok(MoveTracksResult {
+        transaction_id: if journal_error.is_none() {
+            tx.id
+        } else {
+            String::new()
+        },
+        moved: tx.file_moves,
+        updated_playlists: tx.playlist_changes.into_iter().map(|c| c.path).collect(),
+        journal_error,
+    })
+}
+
+#[tauri::command]
+pub async fn move_tracks(
+    app: AppHandle,
+    music_root: String,
+    playlist_dir: Option<String>,
+    path_prefix: Option<String>,
+    moves: Vec<TrackMove>,
+) -> Result<MoveTracksResult, String> {
+    let label = format!("Move {} item(s)", moves.len());
+    perform_moves(
+        &app,
+        &music_root,
+        playlist_dir.as_deref(),
+        path_prefix.as_deref(),
+        &moves,
+        &label,
+    )
+}
+
+/// Characters that are not allowed in file names on Windows / FAT32
+const ILLEGAL_FILENAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names reserved by Windows regardless of extension
+const RESERVED_FILENAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct OrganizeOptions {
+    /// Maximum length of a single path component in UTF-16 units (default 255)
+    pub max_component_length: Option<usize>,
+    /// Maximum length of the whole target path in UTF-16 units (default: unlimited)
+    pub max_path_length: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OrganizePreviewItem {
+    pub source: String,
+    pub target: String,
+    /// "move" | "unchanged" | "collision" | "exists" | "too-long"
+    pub status: String,
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+/// Truncate `s` to at most `max` UTF-16 units without splitting a character.
+fn truncate_utf16(s: &str, max: usize) -> String {
+    let mut len = 0;
+    s.chars()
+        .take_while(|c| {
+            len += c.len_utf16();
+            len <= max
+        })
+        .collect()
+}
+
+/// Make a single path component safe for Windows / FAT32 file systems.
+fn sanitize_component(name: &str, max_len: usize, is_file: bool) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || ILLEGAL_FILENAME_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    // Keep the extension intact when shortening a file name
+    let (stem, ext) = match replaced.rfind('.') {
+        Some(i) if is_file && i > 0 => (&replaced[..i], &replaced[i..]),
+        _ => (replaced.as_str(), ""),
+    };
+    let stem = truncate_utf16(stem, max_len.saturating_sub(utf16_len(ext)));
+    let mut stem = stem.trim_start().trim_end_matches([' ', '.']).to_string();
+
+    if stem.is_empty() {
+        stem.push('_');
+    }
+    let upper = stem.to_uppercase();
+    if RESERVED_FILENAMES
+        .iter()
+        .any(|r| upper == *r || upper.starts_with(&format!("{}.", r)))
+    {
+        stem.insert(0, '_');
+    }
+
+    format!("{}{}", stem, ext)
+}
+
+fn template_value(field: &str, meta: &AudioMetadata, path: &Path) -> Result<TemplateValue, String> {
+    let text = |s: &str| {
+        TemplateValue::Text(if s.is_empty() {
+            "Unknown".to_string()
+        } else {
+            s.to_string()
+        })
+    };
+
+    Ok(match field {
+        "title" => text(&meta.title),
+        "artist" => text(&meta.artist),
+        "albumartist" => text(if meta.album_artist.is_empty() {
+            &meta.artist
+        } else {
+            &meta.album_artist
+        }),
+        "album" => text(&meta.album),
+        "genre" => text(&meta.genre),
+        "composer" => text(&meta.composer),
+        "year" => TemplateValue::Number(meta.year),
+        "track" => TemplateValue::Number(meta.track_number),
+        "totaltracks" => TemplateValue::Number(meta.total_tracks),
+        "disc" => TemplateValue::Number(meta.disc_number),
+        "totaldiscs" => TemplateValue::Number(meta.total_discs),
+        "ext" => TemplateValue::Text(
+            path.extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        ),
+        "filename" => TemplateValue::Text(
+            path.file_stem()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        ),
+        _ => return Err(format!("Unknown template field: {{{}}}", field)),
+    })
+}
+
+enum TemplateValue {
+    Text(String),
+    Number(i64),
+}
+
+/// Render one `/`-separated segment of the template, e.g. `{disc}-{track:02} {title}.{ext}`.
+fn render_segment(segment: &str, meta: &AudioMetadata, path: &Path) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = segment;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed placeholder in template: {}", segment))?;
+        let placeholder = &rest[start + 1..start + end];
+        let (field, width) = match placeholder.split_once(':') {
+            Some((f, w)) => (
+                f,
+                w.parse::<usize>()
+                    .map_err(|_| format!("Invalid width in template: {{{}}}", placeholder))?,
+            ),
+            None => (placeholder, 0),
+        };
+
+        let value = match template_value(&field.to_lowercase(), meta, path)? {
+            TemplateValue::Text(s) => s,
+            TemplateValue::Number(n) => format!("{:0width$}", n, width = width),
+        };
+        // Values must never introduce new directories
+        out.push_str(&value.replace(['/', '\\'], "_"));
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+fn render_target(
+    root: &Path,
+    source: &Path,
+    template: &str,
+    options: &OrganizeOptions,
+) -> Result<PathBuf, String> {
+    let meta = read_audio_metadata(source, false);
+    let max_len = options.max_component_length.unwrap_or(255);
+
+    let template = if template.contains("{ext}") {
+        template.to_string()
+    } else {
+        format!("{}.{{ext}}", template)
+    };
+    let segments: Vec<&str> = template
+        .split(['/', '\\'])
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut target = root.to_path_buf();
+    for (i, segment) in segments.iter().enumerate() {
+        let rendered = render_segment(segment, &meta, source)?;
+        target.push(sanitize_component(
+            &rendered,
+            max_len,
+            i == segments.len() - 1,
+        ));
+    }
+
+    Ok(target)
+}
+
+fn plan_organize(
+    music_root: &str,
+    paths: &[String],
+    template: &str,
+    options: &OrganizeOptions,
+) -> Result<Vec<OrganizePreviewItem>, String> {
+    let root = normalize_path(Path::new(music_root));
+
+    let mut items = Vec::new();
+    for path in paths {
+        let source = normalize_path(Path::new(path));
+        if !source.starts_with(&root) {
+            return Err(format!("Source is outside the music root: {}", path));
+        }
+        let target = render_target(&root, &source, template, options)?;
+
+        let status = if target == source {
+            "unchanged"
+        } else if options
+            .max_path_length
+            .is_some_and(|max| utf16_len(&target.to_string_lossy()) > max)
+        {
+            "too-long"
+        } else if target.exists() && !is_same_file(&source, &target) {
+            "exists"
+        } else {
+            "move"
+        };
+
+        items.push(OrganizePreviewItem {
+            source: source.to_string_lossy().to_string(),
+            target: target.to_string_lossy().to_string(),
+            status: status.to_string(),
+        });
+    }
+
+    // Two tracks rendering to the same file (compared case-insensitively, as on
+    // Windows / FAT32) would overwrite each other
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for item in &items {
+        *counts.entry(item.target.to_lowercase()).or_default() += 1;
+    }
+    for item in &mut items {
+        if counts[&item.target.to_lowercase()] > 1 {
+            item.status = "collision".to_string();
+        }
+    }
+
+    Ok(items)
+}
+
+/// Compute the target path of every track from its tags without touching any file.
+#[tauri::command]
+pub async fn preview_organize_tracks(
+    music_root: String,
+    paths: Vec<String>,
+    template: String,
+    options: Option<OrganizeOptions>,
+) -> Result<Vec<OrganizePreviewItem>, String> {
+    plan_organize(&music_root, &paths, &template, &options.unwrap_or_default())
+}
+
+/// Move tracks to their template-based locations and fix up affected playlists.
+/// Fails without moving anything if the plan contains collisions or other problems.
+#[tauri::command]
+pub async fn organize_tracks(
+    app: AppHandle,
+    music_root: String,
+    playlist_dir: Option<String>,
+    path_prefix: Option<String>,
+    paths: Vec<String>,
+    template: String,
+    options: Option<OrganizeOptions>,
+) -> Result<MoveTracksResult, String> {
+    let items = plan_organize(&music_root, &paths, &template, &options.unwrap_or_default())?;
+
+    if let Some(bad) = items
+        .iter()
+        .find(|i| i.status != "move" && i.status != "unchanged")
+    {
+        return Err(format!("Cannot organize {} ({})", bad.source, bad.status));
+    }
+
+    let moves: Vec<TrackMove> = items
+        .into_iter()
+        .filter(|i| i.status == "move")
+        .map(|i| TrackMove {
+            from: i.source,
+            to: i.target,
+        })
+        .collect();
+
+    let label = format!("Organize {} file(s)", moves.len());
+    perform_moves(
+        &app,
+        &music_root,
+        playlist_dir.as_deref(),
+        path_prefix.as_deref(),
+        &moves,
+        &label,
+    )
+}
@@ -0,0 +1,473 @@
+use crate::commands::artwork::{shrink_picture, ArtworkOptions};
+use crate::commands::fs::{
+    check_picture_format, parse_data_uri, picture_data_uri, picture_mime, save_primary_tag,
+    WEBP_MIME,
+};
+use crate::commands::journal::{FileResult, TagEditRecorder};
+use crate::commands::write_options::TagWriteOptions;
+use lofty::picture::{Picture, PictureInformation, PictureType};
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::Tag;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Names of the APIC picture types, indexed by their ID3v2 type byte
+const PICTURE_TYPE_NAMES: &[&str] = &[
+    "other",
+    "icon",
+    "otherIcon",
+    "coverFront",
+    "coverBack",
+    "leaflet",
+    "media",
+    "leadArtist",
+    "artist",
+    "conductor",
+    "band",
+    "composer",
+    "lyricist",
+    "recordingLocation",
+    "duringRecording",
+    "duringPerformance",
+    "screenCapture",
+    "brightFish",
+    "illustration",
+    "bandLogo",
+    "publisherLogo",
+];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PictureInfo {
+    /// Position in the tag; pass it back to replace, remove or extract the picture
+    pub index: usize,
+    /// "coverFront" | "coverBack" | "leaflet" | "artist" | ...
+    #[serde(rename = "pictureType")]
+    pub picture_type: String,
+    pub description: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    /// Pixel size, 0 if the image could not be inspected
+    pub width: u32,
+    pub height: u32,
+    /// Size of the image data in bytes
+    pub size: usize,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PictureInput {
+    pub picture_type: String,
+    pub description: Option<String>,
+    /// Image as a data URI (`data:image/jpeg;base64,...`)
+    pub data: String,
+}
+
+pub(crate) fn picture_type_name(pic_type: PictureType) -> &'static str {
+    PICTURE_TYPE_NAMES
+        .get(pic_type.as_u8() as usize)
+        .copied()
+        .unwrap_or("other")
+}
+
+pub(crate) fn parse_picture_type(name: &str) -> Result<PictureType, String> {
+    PICTURE_TYPE_NAMES
+        .iter()
+        .position(|n| n.eq_ignore_ascii_case(name))
+        .map(|i| PictureType::from_u8(i as u8))
+        .ok_or_else(|| format!("Unknown picture type: {}", name))
+}
+
+/// A set of picture types, as named in `remove_pictures` and `strip_artwork`.
+pub(crate) struct PictureSelection {
+    /// `None` selects every type
+    types: Option<Vec<PictureType>>,
+}
+
+impl PictureSelection {
+    /// Parse picture type names; "all" selects every type.
+    pub(crate) fn parse(names: &[String]) -> Result<Self, String> {
+        if names.iter().any(|n| n.eq_ignore_ascii_case("all")) {
+            return Ok(PictureSelection { types: None });
+        }
+        let types = names
+            .iter()
+            .map(|n| parse_picture_type(n))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(PictureSelection { types: Some(types) })
+    }
+
+    pub(crate) fn all() -> Self {
+        PictureSelection { types: None }
+    }
+
+    pub(crate) fn contains(&self, pic_type: PictureType) -> bool {
+        match &self.types {
+            Some(types) => types.contains(&pic_type),
+            None => true,
+        }
+    }
+
+    /// Remove the selected pictures from `tag`.
+    pub(crate) fn remove_from(&self, tag: &mut Tag) {
+        for i in (0..tag.pictures().len()).rev() {
+            if self.contains(tag.pictures()[i].pic_type()) {
+                tag.remove_picture(i);
+            }
+        }
+    }
+}
+
+pub(crate) fn file_extension(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/bmp" => "bmp",
+        "image/tiff" => "tif",
+        WEBP_MIME => "webp",
+        _ => "jpg",
+    }
+}
+
+/// Pixel size of a picture; lofty does not know every format (e.g. WebP), so
+/// the image header is read as a fallback.
+fn dimensions(pic: &Picture) -> (u32, u32) {
+    if let Ok(info) = PictureInformation::from_picture(pic) {
+        if info.width > 0 && info.height > 0 {
+            return (info.width, info.height);
+        }
+    }
+    image::ImageReader::new(std::io::Cursor::new(pic.data()))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok())
+        .unwrap_or((0, 0))
+}
+
+fn describe(index: usize, pic: &Picture) -> PictureInfo {
+    let (width, height) = dimensions(pic);
+    PictureInfo {
+        index,
+        picture_type: picture_type_name(pic.pic_type()).to_string(),
+        description: pic.description().unwrap_or_default().to_string(),
+        mime_type: picture_mime(pic),
+        width,
+        height,
+        size: pic.data().len(),
+    }
+}
+
+fn to_picture(input: &PictureInput, artwork: Option<&ArtworkOptions>) -> Result<Picture, String> {
+    let pic_type = parse_picture_type(&input.picture_type)?;
+    let data = parse_data_uri(&input.data)?;
+    let description = input.description.clone().filter(|d| !d.is_empty());
+    let pic = Picture::new_unchecked(pic_type, Some(data.mime_type), description, data.bytes);
+    match artwork {
+        Some(options) => Ok(shrink_picture(&pic, options)?.unwrap_or(pic)),
+        None => Ok(pic),
+    }
+}
+
+/// Every picture of the file's primary tag, in tag order.
+pub(crate) fn read_pictures(file_path: &Path) -> Result<Vec<Picture>, String> {
+    let tagged = Probe::open(file_path)
+        .and_then(|p| p.read())
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(tagged
+        .primary_tag()
+        .map(|tag| tag.pictures().to_vec())
+        .unwrap_or_default())
+}
+
+/// Replace all pictures of the file's primary tag with `pictures`, in that
+/// order, saving per `options`.
+pub(crate) fn write_pictures(
+    file_path: &Path,
+    pictures: Vec<Picture>,
+    options: &TagWriteOptions,
+) -> Result<(), String> {
+    let mut tagged = Probe::open(file_path)
+        .and_then(|p| p.read())
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let file_type = tagged.file_type();
+
+    let tag = match tagged.primary_tag_mut() {
+        Some(t) => t,
+        None if pictures.is_empty() => return Ok(()),
+        None => {
+            tagged.insert_tag(Tag::new(file_type.primary_tag_type()));
+            tagged.primary_tag_mut().unwrap()
+        }
+    };
+
+    for pic in &pictures {
+        if let Some(mime) = pic.mime_type() {
+            check_picture_format(tag.tag_type(), mime)?;
+        }
+    }
+    while !tag.pictures().is_empty() {
+        tag.remove_picture(0);
+    }
+    for pic in pictures {
+        tag.push_picture(pic);
+    }
+
+    save_primary_tag(file_path, file_type, tag, options)
+}
+
+fn list(file_path: &Path) -> Result<Vec<PictureInfo>, String> {
+    Ok(read_pictures(file_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, pic)| describe(i, pic))
+        .collect())
+}
+
+fn check_index(pictures: &[Picture], index: usize) -> Result<(), String> {
+    if index >= pictures.len() {
+        return Err(format!("Picture index out of range: {}", index));
+    }
+    Ok(())
+}
+
+/// Apply `edit` to the file's picture list as one journaled tag edit.
+/// `options` applies as in `write_audio_tags`.
+fn edit_pictures(
+    app: &AppHandle,
+    path: &str,
+    label: &str,
+    options: Option<TagWriteOptions>,
+    edit: impl FnOnce(&mut Vec<Picture>) -> Result<(), String>,
+) -> Result<Vec<PictureInfo>, String> {
+    let file_path = Path::new(path);
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let mut recorder = TagEditRecorder::begin(app, label)?.with_write_options(options.clone());
+    let result = recorder.edit(file_path, |p| {
+        let mut pictures = read_pictures(p)?;
+        edit(&mut pictures)?;
+        write_pictures(p, pictures, &options)
+    });
+    recorder.finish()?;
+    result?;
+    list(file_path)
+}
+
+#[tauri::command]
+pub async fn list_pictures(path: String) -> Result<Vec<PictureInfo>, String> {
+    let file_path = Path::new(&path);
+    if !file_path.exists() {
+        return Err(format!("File not found: {}", path));
+    }
+    list(file_path)
+}
+
+/// One picture as a data URI, for display.
+#[tauri::command]
+pub async fn get_picture(path: String, index: usize) -> Result<String, String> {
+    let pictures = read_pictures(Path::new(&path))?;
+    check_index(&pictures, index)?;
+    Ok(picture_data_uri(&pictures[index]))
+}
+
+#[tauri::command]
+pub async fn add_picture(
+    app: AppHandle,
+    path: String,
+    picture: PictureInput,
+    artwork: Option<ArtworkOptions>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<PictureInfo>, String> {
+    let pic = to_picture(&picture, artwork.as_ref())?;
+    edit_pictures(&app, &path, "Add picture", options, |pictures| {
+        pictures.push(pic);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn replace_picture(
+    app: AppHandle,
+    path: String,
+    index: usize,
+    picture: PictureInput,
+    artwork: Option<ArtworkOptions>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<PictureInfo>, String> {
+    let pic = to_picture(&picture, artwork.as_ref())?;
+    edit_pictures(&app, &path, "Replace picture", options, |pictures| {
+        check_index(pictures, index)?;
+        pictures[index] = pic;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn remove_picture(
+    app: AppHandle,
+    path: String,
+    index: usize,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<PictureInfo>, String> {
+    edit_pictures(&app, &path, "Remove picture", options, |pictures| {
+        check_index(pictures, index)?;
+        pictures.remove(index);
+        Ok(())
+    })
+}
+
+/// Reorder the pictures; `order` lists the current indices in their new order.
+#[tauri::command]
+pub async fn reorder_pictures(
+    app: AppHandle,
+    path: String,
+    order: Vec<usize>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<PictureInfo>, String> {
+    edit_pictures(&app, &path, "Reorder pictures", options, |pictures| {
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        if sorted != (0..pictures.len()).collect::<Vec<_>>() {
+            return Err("Order must list every picture index exactly once".to_string());
+        }
+        let reordered = order.iter().map(|&i| pictures[i].clone()).collect();
+        *pictures = reordered;
+        Ok(())
+    })
+}
+
+/// Save one picture as an image file. When `destination` is a directory the
+/// file is named after the picture type. An existing file is only replaced
+/// with `overwrite` set. Returns the written path.
+#[tauri::command]
+pub async fn extract_picture(
+    path: String,
+    index: usize,
+    destination: String,
+    overwrite: bool,
+) -> Result<String, String> {
+    let pictures = read_pictures(Path::new(&path))?;
+    check_index(&pictures, index)?;
+    let pic = &pictures[index];
+
+    let mut target = PathBuf::from(&destination);
+    if target.is_dir() {
+        target = target.join(format!(
+            "{}.{}",
+            picture_type_name(pic.pic_type()),
+            file_extension(&picture_mime(pic))
+        ));
+    }
+    if target.exists() && !overwrite {
+        return Err(format!("File already exists: {}", target.display()));
+    }
+    std::fs::write(&target, pic.data()).map_err(|e| format!("Failed to write image: {}", e))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ArtworkStripOptions {
+    /// Picture types to strip; empty or "all" strips every type
+    #[serde(default)]
+    pub picture_types: Vec<String>,
+    /// Only strip pictures of at least this many bytes, e.g. to get rid of
+    /// oversized scans while keeping small covers
+    pub min_bytes: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArtworkStripResult {
+    pub path: String,
+    pub removed: usize,
+    #[serde(rename = "bytesSaved")]
+    pub bytes_saved: usize,
+    pub error: Option<String>,
+}
+
+impl FileResult for ArtworkStripResult {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArtworkProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+/// Remove the pictures matching `options`; returns (count, bytes) removed.
+fn strip_file(
+    recorder: &mut TagEditRecorder,
+    path: &Path,
+    selection: &PictureSelection,
+    min_bytes: usize,
+) -> Result<(usize, usize), String> {
+    let pictures = read_pictures(path)?;
+    let (stripped, kept): (Vec<Picture>, Vec<Picture>) = pictures
+        .into_iter()
+        .partition(|pic| selection.contains(pic.pic_type()) && pic.data().len() >= min_bytes);
+    if stripped.is_empty() {
+        return Ok((0, 0));
+    }
+
+    recorder.write_pictures(path, kept)?;
+    Ok((
+        stripped.len(),
+        stripped.iter().map(|pic| pic.data().len()).sum(),
+    ))
+}
+
+/// Strip embedded artwork from many files. Emits `artwork-progress` after each
+/// file and returns one result per path instead of failing the whole batch.
+/// `write_options` applies as in `write_audio_tags`.
+#[tauri::command]
+pub async fn strip_artwork(
+    app: AppHandle,
+    paths: Vec<String>,
+    options: ArtworkStripOptions,
+    write_options: Option<TagWriteOptions>,
+) -> Result<Vec<ArtworkStripResult>, String> {
+    let write_options = write_options.unwrap_or_default();
+    write_options.validate()?;
+    let selection = if options.picture_types.is_empty() {
+        PictureSelection::all()
+    } else {
+        PictureSelection::parse(&options.picture_types)?
+    };
+    let min_bytes = options.min_bytes.unwrap_or(0);
+
+    let total = paths.len();
+    let mut results = Vec::with_capacity(total);
+    let mut recorder =
+        TagEditRecorder::begin(&app, &format!("Strip artwork from {} file(s)", total))?
+            .with_write_options(write_options);
+
+    for (i, path) in paths.iter().enumerate() {
+        let result = strip_file(&mut recorder, Path::new(path), &selection, min_bytes);
+        let (removed, bytes_saved) = result.as_ref().copied().unwrap_or((0, 0));
+        results.push(ArtworkStripResult {
+            path: path.clone(),
+            removed,
+            bytes_saved,
+            error: result.err(),
+        });
+
+        let _ = app.emit(
+            "artwork-progress",
+            ArtworkProgress {
+                current: i + 1,
+                total,
+                path: path.clone(),
+            },
+        );
+    }
+
+    recorder.finish_with_results(&mut results);
+    Ok(results)
+}
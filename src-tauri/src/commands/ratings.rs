@@ -0,0 +1,259 @@
+use crate::commands::custom_fields::item_key;
+use lofty::id3::v2::{BinaryFrame, Frame, FrameId, Id3v2Tag, PopularimeterFrame};
+use lofty::prelude::*;
+use lofty::tag::{ItemKey, ItemValue, Tag, TagItem, TagType};
+use std::borrow::Cow;
+
+/// Rating, play count and loved flag, normalized across players.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Popularity {
+    /// 0-100 where every 20 is one star; 0 = unrated
+    pub rating: i64,
+    pub play_count: i64,
+    pub loved: bool,
+}
+
+/// Changes to a file's popularity; `None` leaves that part as it is.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PopularityUpdate {
+    pub rating: Option<i64>,
+    pub play_count: Option<i64>,
+    pub loved: Option<bool>,
+}
+
+impl PopularityUpdate {
+    /// The parts of the update that differ from `current`. Values read from the
+    /// file and sent back unchanged must not rewrite it: that would re-quantize
+    /// POPM ratings, overwrite every player's counter and add fields the file
+    /// never had.
+    fn changes_from(&self, current: &Popularity) -> PopularityUpdate {
+        PopularityUpdate {
+            rating: self.rating.filter(|rating| *rating != current.rating),
+            play_count: self.play_count.filter(|count| *count != current.play_count),
+            loved: self.loved.filter(|loved| *loved != current.loved),
+        }
+    }
+}
+
+/// POPM bytes Windows Media Player writes for 1-5 stars; most players follow them
+const POPM_STARS: [u8; 5] = [1, 64, 128, 196, 255];
+
+/// Owner of the POPM frame created when the file has none yet
+const POPM_EMAIL: &str = "Windows Media Player 9 Series";
+
+const LOVED: &str = "LOVED";
+
+fn rating_from_popm(byte: u8) -> i64 {
+    match byte {
+        0 => 0,
+        1..=31 => 20,
+        32..=95 => 40,
+        96..=159 => 60,
+        160..=223 => 80,
+        _ => 100,
+    }
+}
+
+fn popm_from_rating(rating: i64) -> u8 {
+    if rating <= 0 {
+        return 0;
+    }
+    let stars = ((rating + 10) / 20).clamp(1, 5);
+    POPM_STARS[(stars - 1) as usize]
+}
+
+/// Vorbis / APE `RATING` is either 1-5 stars or 0-100 depending on the player.
+fn rating_from_text(value: &str) -> Option<i64> {
+    let value = value.parse::<f64>().ok()?;
+    if value <= 5.0 {
+        Some((value * 20.0).round() as i64)
+    } else {
+        Some(value.round().clamp(0.0, 100.0) as i64)
+    }
+}
+
+fn is_true(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes"
+    )
+}
+
+/// PCNT holds a big-endian counter of at least four bytes.
+fn parse_counter(data: &[u8]) -> i64 {
+    data.iter()
+        .take(8)
+        .fold(0u64, |count, byte| (count << 8) | u64::from(*byte)) as i64
+}
+
+fn counter_bytes(count: u64) -> Vec<u8> {
+    let bytes = count.to_be_bytes();
+    let skip = (count.leading_zeros() / 8).min(4) as usize;
+    bytes[skip..].to_vec()
+}
+
+/// Popularity of an ID3v2 tag. With frames from several players, the highest
+/// rating and count win.
+pub(crate) fn from_id3v2(tag: &Id3v2Tag) -> Popularity {
+    let mut popularity = Popularity::default();
+    for frame in tag {
+        match frame {
+            Frame::Popularimeter(popm) => {
+                popularity.rating = popularity.rating.max(rating_from_popm(popm.rating));
+                popularity.play_count = popularity.play_count.max(popm.counter as i64);
+            }
+            Frame::Binary(pcnt) if frame.id_str() == "PCNT" => {
+                popularity.play_count = popularity.play_count.max(parse_counter(&pcnt.data));
+            }
+            Frame::UserText(text) if text.description.eq_ignore_ascii_case(LOVED) => {
+                popularity.loved = is_true(&text.content);
+            }
+            _ => {}
+        }
+    }
+    popularity
+}
+
+/// Apply the changed parts of `update` to POPM (every player's frame is
+/// updated), PCNT and TXXX:LOVED.
+pub(crate) fn apply_to_id3v2(tag: &mut Id3v2Tag, update: &PopularityUpdate) {
+    let update = &update.changes_from(&from_id3v2(tag));
+    if update.rating.is_some() || update.play_count.is_some() {
+        let popm_id = FrameId::Valid(Cow::Borrowed("POPM"));
+        let mut frames: Vec<PopularimeterFrame> = tag
+            .remove(&popm_id)
+            .filter_map(|frame| match frame {
+                Frame::Popularimeter(popm) => Some(popm),
+                _ => None,
+            })
+            .collect();
+        if frames.is_empty() {
+            frames.push(PopularimeterFrame::new(POPM_EMAIL.to_string(), 0, 0));
+        }
+        for popm in frames {
+            let rating = update.rating.map_or(popm.rating, popm_from_rating);
+            let counter = update
+                .play_count
+                .map_or(popm.counter, |count| count.max(0) as u64);
+            if rating > 0 || counter > 0 {
+                tag.insert(Frame::Popularimeter(PopularimeterFrame::new(
+                    popm.email.to_string(),
+                    rating,
+                    counter,
+                )));
+            }
+        }
+    }
+
+    if let Some(count) = update.play_count {
+        let pcnt_id = FrameId::Valid(Cow::Borrowed("PCNT"));
+        tag.remove(&pcnt_id).for_each(drop);
+        if count > 0 {
+            tag.insert(Frame::Binary(BinaryFrame::new(
+                pcnt_id,
+                counter_bytes(count as u64),
+            )));
+        }
+    }
+
+    match update.loved {
+        Some(true) => {
+            tag.insert_user_text(LOVED.to_string(), "1".to_string());
+        }
+        Some(false) => {
+            tag.remove_user_text(LOVED);
+        }
+        None => {}
+    }
+}
+
+/// Key of the native MP4 `rate` atom, which is not an iTunes freeform atom.
+fn mp4_rate_key() -> ItemKey {
+    ItemKey::from_key(TagType::Mp4Ilst, "rate")
+}
+
+/// Popularity of a Vorbis comment, APE or MP4 tag.
+pub(crate) fn from_tag(tag: &Tag) -> Popularity {
+    let tag_type = tag.tag_type();
+    let text = |name: &str| {
+        tag.get_string(&item_key(tag_type, name))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let rating = if tag_type == TagType::Mp4Ilst {
+        tag.get_string(&mp4_rate_key())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .map(|v| v.round().clamp(0.0, 100.0) as i64)
+    } else {
+        // FMPS_RATING is 0.0-1.0 and unambiguous, so it takes precedence
+        text("FMPS_RATING")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| (v * 100.0).round().clamp(0.0, 100.0) as i64)
+            .or_else(|| text("RATING").and_then(rating_from_text))
+    };
+    let play_count = text("FMPS_PLAYCOUNT")
+        .or_else(|| text("PLAYCOUNT"))
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| v.max(0.0) as i64);
+
+    Popularity {
+        rating: rating.unwrap_or(0),
+        play_count: play_count.unwrap_or(0),
+        loved: text(LOVED).is_some_and(is_true),
+    }
+}
+
+/// Apply the changed parts of `update` to a Vorbis comment, APE or MP4 tag. An
+/// existing `RATING` keeps its scale; FMPS fields are kept in sync.
+pub(crate) fn apply_to_tag(tag: &mut Tag, update: &PopularityUpdate) {
+    let update = &update.changes_from(&from_tag(tag));
+    let tag_type = tag.tag_type();
+    let existing = |tag: &Tag, name: &str| {
+        tag.get_string(&item_key(tag_type, name))
+            .map(str::trim)
+            .map(str::to_string)
+    };
+    let star_scale = existing(tag, "RATING")
+        .and_then(|v| v.parse::<f64>().ok())
+        .is_some_and(|v| v > 0.0 && v <= 5.0);
+    let has_fmps_count = existing(tag, "FMPS_PLAYCOUNT").is_some();
+
+    let mut set = |key: ItemKey, value: Option<String>| match value {
+        Some(value) => {
+            tag.insert(TagItem::new(key, ItemValue::Text(value)));
+        }
+        None => {
+            tag.remove_key(&key);
+        }
+    };
+
+    if let Some(rating) = update.rating {
+        let rating = rating.clamp(0, 100);
+        let rated = (rating > 0).then_some(rating);
+        if tag_type == TagType::Mp4Ilst {
+            set(mp4_rate_key(), rated.map(|r| r.to_string()));
+        } else {
+            let stars = |r: i64| ((r + 10) / 20).clamp(1, 5);
+            set(
+                item_key(tag_type, "RATING"),
+                rated.map(|r| if star_scale { stars(r) } else { r }.to_string()),
+            );
+            set(
+                item_key(tag_type, "FMPS_RATING"),
+                rated.map(|r| (r as f64 / 100.0).to_string()),
+            );
+        }
+    }
+
+    if let Some(count) = update.play_count {
+        let count = (count > 0).then(|| count.to_string());
+        if has_fmps_count {
+            set(item_key(tag_type, "FMPS_PLAYCOUNT"), count.clone());
+        }
+        set(item_key(tag_type, "PLAYCOUNT"), count);
+    }
+    if let Some(loved) = update.loved {
+        set(item_key(tag_type, LOVED), loved.then(|| "1".to_string()));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::commands::m3u::{
+    collect_playlists, resolve_entry_path, write_playlists_atomically, PlaylistDocument,
+};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PathRewriteRule {
+    /// Path prefix (literal mode) or regular expression (regex mode) to look for
+    pub find: String,
+    /// Replacement text. In regex mode `$1`, `${name}` etc. refer to capture groups.
+    /// Rewritten entries use its separator, or keep their own if it has none
+    pub replace: String,
+    /// "literal" | "regex"
+    pub mode: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlaylistRewriteReport {
+    pub path: String,
+    /// Number of track entries in the playlist
+    pub total: usize,
+    /// Entries whose path matched the rule and was rewritten
+    pub changed: usize,
+    /// Entries that do not point to an existing file after the rewrite
+    pub unresolved: usize,
+    /// Whether the playlist file was actually rewritten (always false in dry-run)
+    pub written: bool,
+}
+
+enum Matcher {
+    Literal {
+        find: String,
+        replace: String,
+        case_insensitive: bool,
+    },
+    Regex {
+        regex: Regex,
+        replace: String,
+    },
+}
+
+struct CompiledRule {
+    matcher: Matcher,
+    /// Separator the replacement is written with, if it names one
+    separator: Option<char>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &PathRewriteRule) -> Result<Self, String> {
+        if rule.find.is_empty() {
+            return Err("Rewrite rule must not be empty".to_string());
+        }
+        let matcher = match rule.mode.as_str() {
+            "regex" => {
+                // Anchor the pattern so that it only ever matches a path prefix
+                let regex = RegexBuilder::new(&format!("^(?:{})", rule.find))
+                    .case_insensitive(rule.case_insensitive)
+                    .build()
+                    .map_err(|e| format!("Invalid regular expression: {}", e))?;
+                Matcher::Regex {
+                    regex,
+                    replace: rule.replace.clone(),
+                }
+            }
+            _ => Matcher::Literal {
+                find: rule.find.replace('\\', "/"),
+                replace: rule.replace.replace('\\', "/"),
+                case_insensitive: rule.case_insensitive,
+            },
+        };
+        let separator = if rule.replace.contains('\\') {
+            Some('\\')
+        } else if rule.replace.contains('/') {
+            Some('/')
+        } else {
+            None
+        };
+        Ok(CompiledRule { matcher, separator })
+    }
+
+    /// Apply the rule to a single playlist entry. Returns `None` if it does not
+    /// match. The result keeps the entry's separators unless the replacement
+    /// spells out its own, e.g. to move a Windows path to a Unix one.
+    fn apply(&self, entry: &str) -> Option<String> {
+        let rewritten = self
+            .matcher
+            .apply(&entry.replace('\\', "/"))?
+            .replace('\\', "/");
+        let separator = self
+            .separator
+            .unwrap_or(if entry.contains('\\') { '\\' } else { '/' });
+        Some(if separator == '\\' {
+            rewritten.replace('/', "\\")
+        } else {
+            rewritten
+        })
+    }
+}
+
+impl Matcher {
+    /// Apply the rule to an entry written with `/` separators.
+    fn apply(&self, normalized: &str) -> Option<String> {
+        match self {
+            Matcher::Literal {
+                find,
+                replace,
+                case_insensitive,
+            } => {
+                let head = normalized.get(..find.len())?;
+                let matches = if *case_insensitive {
+                    head.eq_ignore_ascii_case(find)
+                } else {
+                    head == find
+                };
+                let rest = &normalized[find.len()..];
+                // "/mnt/old" must not match "/mnt/older/..."
+                if !matches || !(find.ends_with('/') || rest.is_empty() || rest.starts_with('/')) {
+                    return None;
+                }
+                Some(format!("{}{}", replace, rest))
+            }
+            Matcher::Regex { regex, replace } => {
+                if !regex.is_match(normalized) {
+                    return None;
+                }
+                Some(regex.replace(normalized, replace.as_str()).into_owned())
+            }
+        }
+    }
+}
+
+/// Apply a find/replace rule to the path entries of every playlist returned by
+/// `list_playlists`. With `dry_run` set nothing is written and the report only
+/// shows what would change; otherwise all modified playlists are replaced atomically.
+#[tauri::command]
+pub async fn rewrite_playlist_paths(
+    root: String,
+    playlist_dir: Option<String>,
+    rule: PathRewriteRule,
+    dry_run: bool,
+) -> Result<Vec<PlaylistRewriteReport>, String> {
+    let compiled = CompiledRule::compile(&rule)?;
+
+    let mut reports = Vec::new();
+    let mut pending: Vec<(String, String)> = Vec::new();
+
+    for playlist in collect_playlists(&root, playlist_dir.as_deref()) {
+        let playlist_path = Path::new(&playlist);
+        let dir = playlist_path.parent().ok_or("Invalid playlist path")?;
+        let mut doc = PlaylistDocument::read(playlist_path)?;
+
+        let mut changed = 0;
+        let mut unresolved = 0;
+        for n in 0..doc.entry_count() {
+            let entry = doc.entry_path(n);
+            let effective = match compiled.apply(&entry) {
+                Some(rewritten) if rewritten != entry => {
+                    changed += 1;
+                    doc.set_entry_path(n, &rewritten);
+                    rewritten
+                }
+                _ => entry,
+            };
+            if !resolve_entry_path(&effective, dir).exists() {
+                unresolved += 1;
+            }
+        }
+
+        if changed > 0 && !dry_run {
+            pending.push((playlist.clone(), doc.render()));
+        }
+
+        reports.push(PlaylistRewriteReport {
+            path: playlist,
+            total: doc.entry_count(),
+            changed,
+            unresolved,
+            written: changed > 0 && !dry_run,
+        });
+    }
+
+    if !pending.is_empty() {
+        write_playlists_atomically(&pending)?;
+    }
+
+    Ok(reports)
+}
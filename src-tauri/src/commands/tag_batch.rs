@@ -0,0 +1,185 @@
+use crate::commands::artwork::{shrink_data_uri, ArtworkOptions};
+use crate::commands::fs::{read_tags_for_edit, AudioTags, TagWriteResult, MULTI_VALUE_SEPARATOR};
+use crate::commands::journal::TagEditRecorder;
+use crate::commands::write_options::TagWriteOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+/// What to do with a single field of every file in a batch.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "op", content = "value", rename_all = "lowercase")]
+pub enum FieldPatch {
+    /// Leave the file's current value untouched
+    Keep,
+    /// Overwrite with the given value
+    Set(Value),
+    /// Remove the field from the file
+    Clear,
+}
+
+/// Patch keyed by `AudioTags` field name as seen by the frontend (`title`,
+/// `albumArtist`, `trackNumber`, ...). Fields that are absent are kept.
+pub type AudioTagsPatch = HashMap<String, FieldPatch>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TagWriteProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+/// The value that makes `write_tags_to_path` remove a field of the given JSON type.
+fn cleared(value: &Value) -> Value {
+    match value {
+        Value::String(_) => Value::String(String::new()),
+        Value::Number(_) => Value::from(0),
+        Value::Array(_) => Value::Array(Vec::new()),
+        Value::Object(_) => Value::Object(Default::default()),
+        Value::Bool(_) => Value::Bool(false),
+        Value::Null => Value::Null,
+    }
+}
+
+/// Display-string / value-list pairs of the multi-value fields
+pub(crate) const MULTI_VALUE_FIELDS: &[(&str, &str)] = &[
+    ("artist", "artists"),
+    ("albumArtist", "albumArtists"),
+    ("genre", "genres"),
+    ("composer", "composers"),
+];
+
+fn is_changed(patch: &AudioTagsPatch, key: &str) -> bool {
+    !matches!(patch.get(key), None | Some(FieldPatch::Keep))
+}
+
+/// Apply `patch` on top of `tags`.
+pub(crate) fn apply_patch(tags: &AudioTags, patch: &AudioTagsPatch) -> Result<AudioTags, String> {
+    let mut value = serde_json::to_value(tags).map_err(|e| e.to_string())?;
+    let fields = value
+        .as_object_mut()
+        .ok_or("AudioTags must serialize to an object")?;
+
+    let mut remove_front_cover = false;
+    for (key, op) in patch {
+        let current = fields
+            .get_mut(key)
+            .ok_or_else(|| format!("Unknown tag field: {}", key))?;
+        match op {
+            FieldPatch::Keep => {}
+            FieldPatch::Set(Value::Object(entries)) if key == "customFields" => {
+                // Custom fields are merged per name so that a batch can set one
+                // field without wiping the others; an empty value removes the field
+                if !current.is_object() {
+                    *current = Value::Object(Default::default());
+                }
+                if let Some(map) = current.as_object_mut() {
+                    for (name, v) in entries {
+                        map.insert(name.clone(), v.clone());
+                    }
+                }
+            }
+            FieldPatch::Set(v) => *current = v.clone(),
+            FieldPatch::Clear if key == "coverArt" => {
+                // An empty cover means "leave pictures alone" to the writer, so
+                // the front cover is removed explicitly
+                *current = Value::String(String::new());
+                remove_front_cover = true;
+            }
+            FieldPatch::Clear => *current = cleared(current),
+        }
+    }
+
+    if remove_front_cover {
+        let removed = fields.entry("removePictures").or_insert(Value::Null);
+        if !removed.is_array() {
+            *removed = Value::Array(Vec::new());
+        }
+        if let Some(types) = removed.as_array_mut() {
+            types.push(Value::String("coverFront".to_string()));
+        }
+    }
+
+    // When only the value list was patched, keep the display string in sync so
+    // that the writer does not treat the stale string as an edit
+    for (single, list) in MULTI_VALUE_FIELDS {
+        if is_changed(patch, list) && !is_changed(patch, single) {
+            let joined = fields[*list]
+                .as_array()
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .collect::<Vec<_>>()
+                        .join(MULTI_VALUE_SEPARATOR)
+                })
+                .unwrap_or_default();
+            fields.insert(single.to_string(), Value::String(joined));
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("Invalid tag patch: {}", e))
+}
+
+fn write_patched(
+    recorder: &mut TagEditRecorder,
+    path: &Path,
+    patch: &AudioTagsPatch,
+) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    // Cover art is not loaded, so unless the patch sets one the pictures stay as-is
+    let current = read_tags_for_edit(path, false);
+    let patched = apply_patch(&current, patch)?;
+    recorder.write(path, &patched)
+}
+
+/// Apply a keep/set/clear patch to many files. Emits `tag-write-progress` after
+/// each file and returns one result per path instead of failing the whole batch.
+/// A cover set by the patch is prepared per `artwork` once for all files;
+/// `options` applies to every file as in `write_audio_tags`.
+#[tauri::command]
+pub async fn write_audio_tags_batch(
+    app: AppHandle,
+    paths: Vec<String>,
+    patch: AudioTagsPatch,
+    artwork: Option<ArtworkOptions>,
+    options: Option<TagWriteOptions>,
+) -> Result<Vec<TagWriteResult>, String> {
+    // Reject malformed patches and options up front rather than once per file
+    apply_patch(&AudioTags::default(), &patch)?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let mut patch = patch;
+    if let (Some(options), Some(FieldPatch::Set(Value::String(uri)))) =
+        (&artwork, patch.get_mut("coverArt"))
+    {
+        *uri = shrink_data_uri(uri, options)?;
+    }
+
+    let total = paths.len();
+    let mut results = Vec::with_capacity(total);
+    let mut recorder = TagEditRecorder::begin(&app, &format!("Batch edit {} file(s)", total))?
+        .with_write_options(options);
+
+    for (i, path) in paths.iter().enumerate() {
+        let result = write_patched(&mut recorder, Path::new(path), &patch);
+        results.push(TagWriteResult::from_result(path, result));
+
+        let _ = app.emit(
+            "tag-write-progress",
+            TagWriteProgress {
+                current: i + 1,
+                total,
+                path: path.clone(),
+            },
+        );
+    }
+
+    recorder.finish_with_results(&mut results);
+    Ok(results)
+}
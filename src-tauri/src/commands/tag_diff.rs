@@ -0,0 +1,194 @@
+use crate::commands::fs::{
+    is_current_cover, normalize_for_write, parse_data_uri, picture_mime, read_front_cover,
+    read_tags_for_edit, AudioTags,
+};
+use crate::commands::pictures::{picture_type_name, read_pictures, PictureSelection};
+use crate::commands::tag_batch::{apply_patch, AudioTagsPatch};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FieldChange {
+    /// `AudioTags` field name as seen by the frontend (`title`, `albumArtist`, ...)
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+    /// "set" | "remove"
+    pub change: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TagDiff {
+    pub path: String,
+    pub changes: Vec<FieldChange>,
+    /// Set when the file could not be inspected; `changes` is empty then
+    pub error: Option<String>,
+}
+
+/// Whether `write_tags_to_path` treats the value as "remove this field".
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.is_empty(),
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        Value::Bool(b) => !b,
+        Value::Null => true,
+    }
+}
+
+/// Short description of a picture, so that the diff does not carry the image itself.
+fn describe_cover(mime: &str, bytes: usize) -> Value {
+    Value::String(format!("{} ({} KB)", mime, bytes.div_ceil(1024)))
+}
+
+fn diff_cover(path: &Path, new_uri: &str) -> Result<Option<FieldChange>, String> {
+    // An empty cover leaves existing pictures alone, the current one is kept as it is
+    if new_uri.is_empty() || is_current_cover(path, new_uri) {
+        return Ok(None);
+    }
+    let new = parse_data_uri(new_uri)?;
+    let old = read_front_cover(path);
+
+    Ok(Some(FieldChange {
+        field: "coverArt".to_string(),
+        old: old
+            .map(|(mime, bytes)| describe_cover(&mime, bytes.len()))
+            .unwrap_or(Value::Null),
+        new: describe_cover(new.mime_type.as_str(), new.bytes.len()),
+        change: "set".to_string(),
+    }))
+}
+
+fn diff_removed_pictures(path: &Path, new: &AudioTags) -> Result<Option<FieldChange>, String> {
+    let Some(names) = &new.remove_pictures else {
+        return Ok(None);
+    };
+    let selection = PictureSelection::parse(names)?;
+    let removed: Vec<Value> = read_pictures(path)?
+        .iter()
+        .filter(|pic| selection.contains(pic.pic_type()))
+        // A replaced front cover is already reported by `diff_cover`
+        .filter(|pic| {
+            new.cover_art.is_empty() || pic.pic_type() != lofty::picture::PictureType::CoverFront
+        })
+        .map(|pic| {
+            Value::String(format!(
+                "{} {} ({} KB)",
+                picture_type_name(pic.pic_type()),
+                picture_mime(pic),
+                pic.data().len().div_ceil(1024)
+            ))
+        })
+        .collect();
+    if removed.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(FieldChange {
+        field: "pictures".to_string(),
+        old: Value::Array(removed),
+        new: Value::Null,
+        change: "remove".to_string(),
+    }))
+}
+
+/// Compute what writing `new` over the file's current tags would change.
+pub(crate) fn diff_against_file(path: &Path, new: &AudioTags) -> Result<Vec<FieldChange>, String> {
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    let old = read_tags_for_edit(path, false);
+    // Compared as written, so that e.g. "-6.5" against a stored "-6.50 dB" is no change
+    let new = &normalize_for_write(new, &old)?;
+    let old_value = serde_json::to_value(&old).map_err(|e| e.to_string())?;
+    let new_value = serde_json::to_value(new).map_err(|e| e.to_string())?;
+    let (Some(old_fields), Some(new_fields)) = (old_value.as_object(), new_value.as_object())
+    else {
+        return Err("AudioTags must serialize to an object".to_string());
+    };
+
+    let mut changes = Vec::new();
+    for (field, new_field) in new_fields {
+        // Value lists are reported through their display field (`artist`, ...)
+        if matches!(
+            field.as_str(),
+            "coverArt"
+                | "coverArtSource"
+                | "removePictures"
+                | "artists"
+                | "albumArtists"
+                | "genres"
+                | "composers"
+        ) {
+            continue;
+        }
+        // Fields that are not sent (`None`) are left alone by the writer
+        if new_field.is_null() {
+            continue;
+        }
+        let old_field = old_fields.get(field).cloned().unwrap_or(Value::Null);
+        if *new_field == old_field || (is_empty_value(new_field) && is_empty_value(&old_field)) {
+            continue;
+        }
+        changes.push(FieldChange {
+            field: field.clone(),
+            old: old_field,
+            new: new_field.clone(),
+            change: if is_empty_value(new_field) {
+                "remove"
+            } else {
+                "set"
+            }
+            .to_string(),
+        });
+    }
+    changes.extend(diff_removed_pictures(path, new)?);
+    changes.extend(diff_cover(path, &new.cover_art)?);
+
+    Ok(changes)
+}
+
+pub(crate) fn to_tag_diff(path: &str, result: Result<Vec<FieldChange>, String>) -> TagDiff {
+    match result {
+        Ok(changes) => TagDiff {
+            path: path.to_string(),
+            changes,
+            error: None,
+        },
+        Err(e) => TagDiff {
+            path: path.to_string(),
+            changes: Vec::new(),
+            error: Some(e),
+        },
+    }
+}
+
+/// Dry run of `write_audio_tags`: same inputs, returns the per-field diff
+/// without touching the file.
+#[tauri::command]
+pub async fn preview_audio_tags_write(path: String, tags: AudioTags) -> Result<TagDiff, String> {
+    let result = diff_against_file(Path::new(&path), &tags);
+    Ok(to_tag_diff(&path, result))
+}
+
+/// Dry run of `write_audio_tags_batch`: returns one diff per file.
+#[tauri::command]
+pub async fn preview_audio_tags_batch(
+    paths: Vec<String>,
+    patch: AudioTagsPatch,
+) -> Result<Vec<TagDiff>, String> {
+    apply_patch(&AudioTags::default(), &patch)?;
+
+    Ok(paths
+        .iter()
+        .map(|path| {
+            let file_path = Path::new(path);
+            let result = apply_patch(&read_tags_for_edit(file_path, false), &patch)
+                .and_then(|patched| diff_against_file(file_path, &patched));
+            to_tag_diff(path, result)
+        })
+        .collect())
+}
@@ -0,0 +1,312 @@
+use crate::commands::file_times::modified_millis;
+use crate::commands::folder_art::{find_folder_art, read_image, DEFAULT_ARTWORK_PATTERNS};
+use crate::commands::fs::{read_front_cover, MUSIC_EXTENSIONS};
+use crate::commands::pictures::read_pictures;
+use image::codecs::jpeg::JpegEncoder;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Url};
+
+/// Name of the custom protocol serving artwork to the webview
+pub const ART_PROTOCOL: &str = "lochord-art";
+
+/// Thumbnail edge lengths kept in the cache; requests snap to the next one up
+const THUMBNAIL_SIZES: &[u32] = &[64, 128, 256, 512, 1024];
+
+const THUMBNAIL_QUALITY: u8 = 85;
+
+fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve app cache directory: {}", e))?
+        .join("thumbnails");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Embedded front cover of a track, or its first picture.
+fn embedded_art(path: &Path) -> Option<(String, Vec<u8>)> {
+    read_front_cover(path).or_else(|| {
+        let pictures = read_pictures(path).ok()?;
+        let pic = pictures.first()?;
+        Some((picture_mime(pic), pic.data().to_vec()))
+    })
+}
+
+/// Where the artwork of a request can come from: tracks with embedded
+/// pictures and the folder artwork file.
+struct ArtSources {
+    /// A single track's own pictures come before the folder artwork, an
+    /// album's tracks after it
+    is_track: bool,
+    tracks: Vec<PathBuf>,
+    folder_art: Option<PathBuf>,
+}
+
+impl ArtSources {
+    fn track(path: &Path, patterns: &[String]) -> Self {
+        ArtSources {
+            is_track: true,
+            tracks: vec![path.to_path_buf()],
+            folder_art: path.parent().and_then(|dir| find_folder_art(dir, patterns)),
+        }
+    }
+
+    fn album(dir: &Path, patterns: &[String]) -> Self {
+        ArtSources {
+            is_track: false,
+            tracks: album_tracks(dir),
+            folder_art: find_folder_art(dir, patterns),
+        }
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.tracks.iter().chain(&self.folder_art)
+    }
+
+    /// Name of the index entry of these sources, from their paths.
+    fn identity(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update([u8::from(self.is_track)]);
+        for source in self.paths() {
+            hasher.update(source.to_string_lossy().as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Changes with the modification time or size of any source, so that an
+    /// index entry is only trusted while the sources are unchanged. The size
+    /// catches edits made with the modification time preserved.
+    fn stamp(&self) -> String {
+        let mut hasher = Sha256::new();
+        for source in self.paths() {
+            let len = fs::metadata(source).map(|m| m.len()).unwrap_or(0);
+            hasher.update(modified_millis(source).to_le_bytes());
+            hasher.update(len.to_le_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Embedded artwork of a track, else its folder artwork; for an album the
+    /// folder artwork, else the artwork of its first track that has any.
+    fn read(&self) -> Option<(String, Vec<u8>)> {
+        let folder_art = || self.folder_art.as_deref().and_then(read_image);
+        let embedded = || self.tracks.iter().find_map(|track| embedded_art(track));
+        if self.is_track {
+            embedded().or_else(folder_art)
+        } else {
+            folder_art().or_else(embedded)
+        }
+    }
+}
+
+/// The tracks directly in an album directory, sorted.
+fn album_tracks(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut tracks: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| MUSIC_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .collect();
+    tracks.sort();
+    tracks
+}
+
+/// Write `contents` through a temporary file so that a concurrent request never
+/// reads half of it.
+fn write_atomically(path: &Path, contents: &[u8]) {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    if fs::write(&tmp, contents).is_ok() && fs::rename(&tmp, path).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+fn thumbnail_path(cache: &Path, hash: &str, size: u32) -> PathBuf {
+    cache.join(format!("{}-{}.jpg", hash, size))
+}
+
+/// Index entry of some sources: the file holds their stamp and the hash of
+/// their artwork, so that a request for unchanged sources finds the cached
+/// thumbnails without reading any artwork.
+fn index_path(cache: &Path, identity: &str) -> PathBuf {
+    cache.join(format!("{}.ref", identity))
+}
+
+fn read_index(path: &Path) -> Option<(String, String)> {
+    let contents = fs::read_to_string(path).ok()?;
+    let (stamp, hash) = contents.split_once('\n')?;
+    Some((stamp.to_string(), hash.to_string()))
+}
+
+/// Delete the thumbnails of `hash` unless an index entry still points to them.
+fn prune(cache: &Path, hash: &str) {
+    let Ok(entries) = fs::read_dir(cache) else {
+        return;
+    };
+    let mut thumbnails = Vec::new();
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.ends_with(".ref") {
+            if read_index(&path).is_some_and(|(_, h)| h == hash) {
+                return;
+            }
+        } else if name.starts_with(&format!("{}-", hash)) {
+            thumbnails.push(path);
+        }
+    }
+    for path in thumbnails {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// JPEG thumbnail of `bytes` no larger than `size`, stored in the cache under
+/// `hash`, the hash of `bytes`.
+fn thumbnail(cache: &Path, hash: &str, bytes: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let img =
+        image::load_from_memory(bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+    let img = if img.width().max(img.height()) > size {
+        img.thumbnail(size, size)
+    } else {
+        img
+    };
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, THUMBNAIL_QUALITY)
+        .encode_image(&img.to_rgb8())
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    write_atomically(&thumbnail_path(cache, hash, size), &encoded);
+    Ok(encoded)
+}
+
+fn respond(status: StatusCode, mime: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, mime)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(body)
+        .unwrap_or_default()
+}
+
+fn error(status: StatusCode, message: String) -> Response<Vec<u8>> {
+    respond(status, "text/plain", message.into_bytes())
+}
+
+/// Serve `lochord-art://localhost/track?path=<file>&size=<px>` and
+/// `lochord-art://localhost/album?dir=<directory>&size=<px>`. `size=0` (or no
+/// size) serves the original image; repeated `pattern` parameters override
+/// the folder artwork file names.
+pub fn handle_art_request(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Ok(url) = Url::parse(&request.uri().to_string()) else {
+        return error(StatusCode::BAD_REQUEST, "Invalid artwork URL".to_string());
+    };
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.to_string())
+    };
+    let mut patterns: Vec<String> = url
+        .query_pairs()
+        .filter(|(k, _)| k == "pattern")
+        .map(|(_, v)| v.to_string())
+        .collect();
+    if patterns.is_empty() {
+        patterns = DEFAULT_ARTWORK_PATTERNS
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+    }
+
+    let sources = match (url.path().trim_matches('/'), param("path"), param("dir")) {
+        ("track", Some(path), _) => ArtSources::track(Path::new(&path), &patterns),
+        ("album", _, Some(dir)) => ArtSources::album(Path::new(&dir), &patterns),
+        _ => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown artwork URL: {}", url),
+            )
+        }
+    };
+    let no_artwork = || error(StatusCode::NOT_FOUND, "No artwork".to_string());
+
+    let requested: u32 = param("size").and_then(|s| s.parse().ok()).unwrap_or(0);
+    if requested == 0 {
+        return match sources.read() {
+            Some((mime, bytes)) => respond(StatusCode::OK, &mime, bytes),
+            None => no_artwork(),
+        };
+    }
+    let size = THUMBNAIL_SIZES
+        .iter()
+        .copied()
+        .find(|s| *s >= requested)
+        .unwrap_or(THUMBNAIL_SIZES[THUMBNAIL_SIZES.len() - 1]);
+
+    let cache = match cache_dir(app) {
+        Ok(cache) => cache,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    // Thumbnails are cached per artwork, so that the tracks of an album sharing
+    // a cover share its thumbnails; the index maps the sources to the artwork
+    let index = index_path(&cache, &sources.identity());
+    let stamp = sources.stamp();
+    let indexed = read_index(&index);
+    if let Some((_, hash)) = indexed.as_ref().filter(|(s, _)| *s == stamp) {
+        if let Ok(data) = fs::read(thumbnail_path(&cache, hash, size)) {
+            return respond(StatusCode::OK, "image/jpeg", data);
+        }
+    }
+
+    let art = sources.read();
+    let hash = art
+        .as_ref()
+        .map(|(_, bytes)| format!("{:x}", Sha256::digest(bytes)));
+    match &hash {
+        Some(hash) => write_atomically(&index, format!("{}\n{}", stamp, hash).as_bytes()),
+        None => {
+            let _ = fs::remove_file(&index);
+        }
+    }
+    if let Some((_, old)) = indexed.filter(|(_, old)| Some(old) != hash.as_ref()) {
+        prune(&cache, &old);
+    }
+    let (Some((_, bytes)), Some(hash)) = (art, hash) else {
+        return no_artwork();
+    };
+    if let Ok(data) = fs::read(thumbnail_path(&cache, &hash, size)) {
+        return respond(StatusCode::OK, "image/jpeg", data);
+    }
+    match thumbnail(&cache, &hash, &bytes, size) {
+        Ok(data) => respond(StatusCode::OK, "image/jpeg", data),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// Delete every cached thumbnail and index entry and return the number of bytes freed.
+#[tauri::command]
+pub async fn clear_thumbnail_cache(app: AppHandle) -> Result<u64, String> {
+    let dir = cache_dir(&app)?;
+    let mut freed = 0;
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read cache directory: {}", e))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if fs::remove_file(entry.path()).is_ok() {
+            freed += size;
+        }
+    }
+    Ok(freed)
+}
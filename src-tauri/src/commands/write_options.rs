@@ -0,0 +1,132 @@
+use lofty::config::WriteOptions;
+use lofty::file::FileType;
+use lofty::id3::v2::{Id3v2Tag, Id3v2Version};
+use lofty::prelude::*;
+use lofty::tag::{Tag, TagType};
+use serde::Deserialize;
+use std::path::Path;
+
+/// How a tag write treats the file's tag formats. Single and batch writes take
+/// the same options.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct TagWriteOptions {
+    /// "2.3" | "2.4"; unset keeps the version the file already has (2.4 for new tags)
+    pub id3v2_version: Option<String>,
+    /// ID3v1 tag: "keep" (default, left untouched), "sync" (rewritten from the
+    /// new tags) or "remove"
+    pub id3v1: Option<String>,
+    /// APE tag: "keep" | "sync" | "remove", as for `id3v1`
+    pub ape: Option<String>,
+    /// Put the file's original access and modification time back after the write
+    #[serde(default)]
+    pub preserve_times: bool,
+}
+
+/// What happens to a tag other than the file's primary one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SecondaryMode {
+    Keep,
+    Sync,
+    Remove,
+}
+
+fn parse_mode(value: Option<&str>) -> Result<SecondaryMode, String> {
+    match value {
+        None | Some("keep") => Ok(SecondaryMode::Keep),
+        Some("sync") => Ok(SecondaryMode::Sync),
+        Some("remove") => Ok(SecondaryMode::Remove),
+        Some(other) => Err(format!("Unknown tag mode: {}", other)),
+    }
+}
+
+impl TagWriteOptions {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match self.id3v2_version.as_deref() {
+            None | Some("2.3") | Some("2.4") => {}
+            Some(other) => return Err(format!("Unsupported ID3v2 version: {}", other)),
+        }
+        parse_mode(self.id3v1.as_deref())?;
+        parse_mode(self.ape.as_deref())?;
+        Ok(())
+    }
+
+    /// lofty options for a file whose current ID3v2 tag is `original`.
+    pub(crate) fn lofty_options(&self, original: Option<&Id3v2Tag>) -> WriteOptions {
+        let use_v23 = match self.id3v2_version.as_deref() {
+            Some(version) => version == "2.3",
+            None => original.is_some_and(|tag| tag.original_version() == Id3v2Version::V3),
+        };
+        WriteOptions::default().use_id3v23(use_v23)
+    }
+
+    /// Whether a write may replace or remove ID3v1 / APE tags at all.
+    pub(crate) fn changes_secondary_tags(&self) -> bool {
+        [self.id3v1.as_deref(), self.ape.as_deref()]
+            .into_iter()
+            .any(|mode| !matches!(parse_mode(mode), Ok(SecondaryMode::Keep)))
+    }
+
+    /// The ID3v1 / APE tags a write replaces or removes in a file of `file_type`,
+    /// with what happens to them. The file's primary tag is left out.
+    fn secondary_modes(
+        &self,
+        file_type: FileType,
+    ) -> Result<Vec<(TagType, SecondaryMode)>, String> {
+        let secondary = [
+            (TagType::Id3v1, parse_mode(self.id3v1.as_deref())?),
+            (TagType::Ape, parse_mode(self.ape.as_deref())?),
+        ];
+        Ok(secondary
+            .into_iter()
+            .filter(|(tag_type, mode)| {
+                *mode != SecondaryMode::Keep
+                    && *tag_type != file_type.primary_tag_type()
+                    && file_type.supports_tag_type(*tag_type)
+            })
+            .collect())
+    }
+
+    /// The ID3v1 / APE tags a write replaces or removes in a file of `file_type`.
+    pub(crate) fn changed_secondary_tags(&self, file_type: FileType) -> Vec<TagType> {
+        self.secondary_modes(file_type)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(tag_type, _)| tag_type)
+            .collect()
+    }
+
+    /// Bring the file's ID3v1 and APE tags in line with `primary`, the tag
+    /// about to be written.
+    pub(crate) fn apply_secondary_tags(
+        &self,
+        path: &Path,
+        file_type: FileType,
+        primary: &Tag,
+    ) -> Result<(), String> {
+        for (tag_type, mode) in self.secondary_modes(file_type)? {
+            match mode {
+                SecondaryMode::Keep => {}
+                SecondaryMode::Remove => {
+                    tag_type
+                        .remove_from_path(path)
+                        .map_err(|e| format!("Failed to remove {:?} tag: {}", tag_type, e))?;
+                }
+                SecondaryMode::Sync => {
+                    let mut tag = Tag::new(tag_type);
+                    // Items the format has no field for are rejected by `insert`
+                    for item in primary.items() {
+                        tag.insert(item.clone());
+                    }
+                    if tag_type == TagType::Ape {
+                        for pic in primary.pictures() {
+                            tag.push_picture(pic.clone());
+                        }
+                    }
+                    tag.save_to_path(path, WriteOptions::default())
+                        .map_err(|e| format!("Failed to save {:?} tag: {}", tag_type, e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
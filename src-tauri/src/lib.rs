@@ -1,26 +1,82 @@
 mod commands;
 
+use commands::artwork::start_artwork_normalization;
+use commands::cleanup::{apply_tag_cleanup, preview_tag_cleanup};
+use commands::filename_tags::{apply_tags_from_filename, preview_tags_from_filename};
+use commands::folder_art::{embed_folder_art, export_cover_to_folder};
 use commands::fs::{
     open_path, read_audio_tags, reveal_item_in_dir, scan_music_directory, select_directory,
     select_music_root, write_audio_tags,
 };
+use commands::jobs::{cancel_job, BackgroundJobs};
+use commands::journal::{list_transactions, undo_transaction};
+use commands::loudness::start_loudness_analysis;
+use commands::lyrics::{export_lrc, import_lrc, read_synced_lyrics};
 use commands::m3u::{delete_playlist, list_playlists, load_playlist, save_playlist};
+use commands::mojibake::{preview_mojibake_repair, repair_mojibake};
+use commands::organize::{move_tracks, organize_tracks, preview_organize_tracks};
+use commands::pictures::{
+    add_picture, extract_picture, get_picture, list_pictures, remove_picture, reorder_pictures,
+    replace_picture, strip_artwork,
+};
+use commands::relink::rewrite_playlist_paths;
+use commands::tag_batch::write_audio_tags_batch;
+use commands::tag_diff::{preview_audio_tags_batch, preview_audio_tags_write};
+use commands::thumbnails::{clear_thumbnail_cache, handle_art_request, ART_PROTOCOL};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(BackgroundJobs::default())
+        .register_asynchronous_uri_scheme_protocol(ART_PROTOCOL, |ctx, request, responder| {
+            // Decoding and resizing images must not block the webview
+            let app = ctx.app_handle().clone();
+            std::thread::spawn(move || responder.respond(handle_art_request(&app, &request)));
+        })
         .invoke_handler(tauri::generate_handler![
             select_music_root,
             select_directory,
             scan_music_directory,
             read_audio_tags,
             write_audio_tags,
+            write_audio_tags_batch,
+            read_synced_lyrics,
+            import_lrc,
+            export_lrc,
+            list_pictures,
+            get_picture,
+            add_picture,
+            replace_picture,
+            remove_picture,
+            reorder_pictures,
+            extract_picture,
+            strip_artwork,
+            start_artwork_normalization,
+            export_cover_to_folder,
+            embed_folder_art,
+            clear_thumbnail_cache,
+            preview_audio_tags_write,
+            preview_audio_tags_batch,
+            preview_tags_from_filename,
+            apply_tags_from_filename,
+            preview_tag_cleanup,
+            apply_tag_cleanup,
+            preview_mojibake_repair,
+            repair_mojibake,
             list_playlists,
             load_playlist,
             save_playlist,
             delete_playlist,
+            rewrite_playlist_paths,
+            move_tracks,
+            preview_organize_tracks,
+            organize_tracks,
+            list_transactions,
+            undo_transaction,
+            start_loudness_analysis,
+            cancel_job,
             open_path,
             reveal_item_in_dir,
         ])
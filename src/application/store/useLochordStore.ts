@@ -3,7 +3,7 @@ import { AudioTags } from "../../domain/entities/AudioTags";
 import { Playlist } from "../../domain/entities/Playlist";
 import { SaveExtension } from "../../domain/entities/AppSettings";
 import { scanMusicDirectory, selectMusicRoot } from "../../infrastructure/tauri/fileSystemAdapter";
-import { readAudioTags, writeAudioTags } from "../../infrastructure/tauri/audioTagsAdapter";
+import { readAudioTags, TagWriteOptions, writeAudioTags } from "../../infrastructure/tauri/audioTagsAdapter";
 import { PlaylistRepository } from "../../infrastructure/repositories/PlaylistRepository";
 import { useSettingsStore } from "./useSettingsStore";
 import { create } from "zustand";
@@ -72,6 +72,17 @@ function buildSaveOptions(musicRoot: string | null) {
   };
 }
 
+/** Build tag write options from current settings */
+function buildTagWriteOptions(): TagWriteOptions {
+  const { settings } = useSettingsStore.getState();
+  return {
+    id3v2_version: settings.id3v2Version === "keep" ? null : settings.id3v2Version,
+    id3v1: settings.id3v1Mode,
+    ape: settings.apeMode,
+    preserve_times: settings.preserveFileTimes ?? false,
+  };
+}
+
 export const useLochordStore = create<LochordState>()(
   persist(
     (set, get) => ({
@@ -339,10 +350,11 @@ export const useLochordStore = create<LochordState>()(
         }
         if (tracks.length === 1) {
           const track = tracks[0];
+          const { artworkPatterns } = useSettingsStore.getState().settings;
           // Set immediately with basic info, then load full tags
           set({ selectedTrackForEdit: track, selectedTracksForEdit: [track], isLoadingTags: true });
           try {
-            const tags = await readAudioTags(track.absolutePath);
+            const tags = await readAudioTags(track.absolutePath, artworkPatterns);
             const loaded: Track = {
               ...track,
               title: tags.title,
@@ -363,6 +375,7 @@ export const useLochordStore = create<LochordState>()(
               publisher: tags.publisher,
               isrc: tags.isrc,
               coverArt: tags.coverArt,
+              coverArtSource: tags.coverArtSource,
             };
             set({
               selectedTrackForEdit: loaded,
@@ -374,12 +387,13 @@ export const useLochordStore = create<LochordState>()(
           }
         } else {
           // Multi-select: read tags from disk in parallel to get accurate coverArt etc.
+          const { artworkPatterns } = useSettingsStore.getState().settings;
           set({ selectedTrackForEdit: tracks[0], selectedTracksForEdit: tracks, isLoadingTags: true });
           try {
             const loadedTracks = await Promise.all(
               tracks.map(async (track) => {
                 try {
-                  const tags = await readAudioTags(track.absolutePath);
+                  const tags = await readAudioTags(track.absolutePath, artworkPatterns);
                   return { ...track, ...tags };
                 } catch {
                   return track; // fallback to in-memory on error
@@ -399,7 +413,7 @@ export const useLochordStore = create<LochordState>()(
 
       updateTrackMetadata: async (absolutePath, tags) => {
         try {
-          await writeAudioTags(absolutePath, tags);
+          await writeAudioTags(absolutePath, tags, undefined, buildTagWriteOptions());
 
           // Update track data across the app
           const patch = {
@@ -420,7 +434,8 @@ export const useLochordStore = create<LochordState>()(
             copyright: tags.copyright,
             publisher: tags.publisher,
             isrc: tags.isrc,
-            coverArt: tags.coverArt,
+            // An empty cover without an explicit removal leaves the shown artwork as-is
+            ...(tags.coverArt || tags.removePictures ? { coverArt: tags.coverArt } : {}),
           };
 
           const { libraryTracks, playlists, selectedTrackForEdit, selectedTracksForEdit } = get();
@@ -492,13 +507,15 @@ export const useLochordStore = create<LochordState>()(
             copyright: track.copyright,
             publisher: track.publisher,
             isrc: track.isrc,
-            coverArt: track.coverArt,
+            // Folder artwork is only shown, never embedded implicitly
+            coverArt: track.coverArtSource === "sidecar" ? "" : track.coverArt,
             ...partialTags,
           };
 
           try {
-            await writeAudioTags(absolutePath, fullTags);
-            results.push({ absolutePath, patch: fullTags });
+            await writeAudioTags(absolutePath, fullTags, undefined, buildTagWriteOptions());
+            const shownCover = fullTags.coverArt || fullTags.removePictures ? fullTags.coverArt : track.coverArt;
+            results.push({ absolutePath, patch: { ...fullTags, coverArt: shownCover } });
           } catch (e) {
             errors.push(`${absolutePath}: ${e}`);
           }
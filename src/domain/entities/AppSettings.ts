@@ -2,6 +2,8 @@ export type PathMode = "relative" | "absolute" | "relative-from-root" | "relativ
 export type SaveExtension = "m3u8" | "m3u" | "txt" | "csv";
 export type ColorTheme = "dark" | "light" | "system";
 export type Language = "ja" | "en" | "ko" | "zh";
+export type Id3v2Version = "keep" | "2.3" | "2.4";
+export type SecondaryTagMode = "keep" | "sync" | "remove";
 
 export interface AppSettings {
   // Playlist
@@ -18,6 +20,15 @@ export interface AppSettings {
   // Scan
   scanExtensions: string[];
   excludePatterns: string[];
+
+  // Artwork
+  artworkPatterns: string[]; // folder artwork file names in priority order, "*" = wildcard
+
+  // Tag writing
+  id3v2Version: Id3v2Version; // "keep" = the version the file already has
+  id3v1Mode: SecondaryTagMode;
+  apeMode: SecondaryTagMode;
+  preserveFileTimes: boolean; // keep mtime/atime when writing tags
 }
 
 export const DEFAULT_SETTINGS: AppSettings = {
@@ -30,4 +41,9 @@ export const DEFAULT_SETTINGS: AppSettings = {
   language: "ja",
   scanExtensions: ["flac", "mp3", "aac", "wav", "m4a", "ogg", "opus"],
   excludePatterns: [],
+  artworkPatterns: ["cover.*", "folder.*", "front.*", "album.*", "albumart*.*"],
+  id3v2Version: "keep",
+  id3v1Mode: "keep",
+  apeMode: "keep",
+  preserveFileTimes: false,
 };
@@ -1,3 +1,8 @@
+export type LyricLine = {
+  timeMs: number;
+  text: string;
+};
+
 export type AudioTags = {
   title: string;
   artist: string;
@@ -17,4 +22,39 @@ export type AudioTags = {
   publisher: string;
   isrc: string;
   coverArt: string; // base64 data URI
+  artists?: string[]; // all values; `artist` is them joined with "; ", split on ";" again only when edited
+  albumArtists?: string[];
+  genres?: string[];
+  composers?: string[];
+  // ReplayGain and MusicBrainz ids: omitted = keep as-is, "" = remove
+  replayGainTrackGain?: string; // e.g. "-6.54 dB"
+  replayGainTrackPeak?: string; // e.g. "0.988547"
+  replayGainAlbumGain?: string;
+  replayGainAlbumPeak?: string;
+  musicBrainzTrackId?: string; // recording id
+  musicBrainzReleaseId?: string;
+  musicBrainzArtistId?: string;
+  musicBrainzReleaseGroupId?: string;
+  // Extended fields: omitted = keep as-is, "" / 0 = remove
+  originalYear?: number; // 0 = unknown
+  releaseDate?: string; // full date, e.g. "2001-03-07"
+  catalogNumber?: string;
+  barcode?: string;
+  label?: string; // same field as publisher (TPUB / LABEL)
+  conductor?: string;
+  lyricist?: string;
+  remixer?: string;
+  grouping?: string;
+  artistSort?: string;
+  albumSort?: string;
+  titleSort?: string;
+  mediaType?: string;
+  // Popularity: omitted = keep as-is
+  rating?: number; // 0-100, 20 per star; 0 = unrated
+  playCount?: number;
+  loved?: boolean;
+  customFields?: Record<string, string> | null; // TXXX / Vorbis / freeform; omitted = keep as-is
+  syncedLyrics?: LyricLine[] | null; // SYLT or LRC lyrics; omitted = keep as-is, [] = remove
+  coverArtSource?: string; // "embedded" | "sidecar" (folder artwork) | ""; ignored when writing
+  removePictures?: string[] | null; // picture types ("coverFront", ..., "all") removed before coverArt is applied
 };
@@ -17,7 +17,48 @@ export type Track = {
   publisher: string;
   isrc: string;
   coverArt: string; // base64 data URI (empty string = no cover)
+  coverArtSource?: string; // "embedded" | "sidecar" (folder artwork) | ""
+  artists?: string[]; // all values; `artist` is them joined with "; "
+  albumArtists?: string[];
+  genres?: string[];
+  composers?: string[];
+  replayGainTrackGain?: string; // e.g. "-6.54 dB"
+  replayGainTrackPeak?: string; // e.g. "0.988547"
+  replayGainAlbumGain?: string;
+  replayGainAlbumPeak?: string;
+  musicBrainzTrackId?: string; // recording id
+  musicBrainzReleaseId?: string;
+  musicBrainzArtistId?: string;
+  musicBrainzReleaseGroupId?: string;
+  originalYear?: number; // 0 = unknown
+  releaseDate?: string; // full date, e.g. "2001-03-07"
+  catalogNumber?: string;
+  barcode?: string;
+  label?: string;
+  conductor?: string;
+  lyricist?: string;
+  remixer?: string;
+  grouping?: string;
+  artistSort?: string;
+  albumSort?: string;
+  titleSort?: string;
+  mediaType?: string;
+  rating?: number; // 0-100, 20 per star; 0 = unrated
+  playCount?: number;
+  loved?: boolean;
   duration: number; // seconds
   relativePath: string; // path written in M3U8
   absolutePath: string; // path for UI operations
+  modifiedAt?: number; // ms since epoch of the last change not made by our own tag edits
+  tagsModifiedAt?: number; // ms since epoch of our latest tag edit, 0 if the file changed since
+  // Technical properties; 0 / "" = unknown
+  bitrate?: number; // kbps
+  sampleRate?: number; // Hz
+  bitDepth?: number; // lossless files only
+  channels?: number;
+  codec?: string; // "MP3" | "AAC" | "ALAC" | "FLAC" | "Opus" | "Vorbis" | "PCM" | ...
+  container?: string; // "MPEG" | "MP4" | "FLAC" | "Ogg" | "WAV" | "AIFF" | ...
+  fileSize?: number; // bytes
+  lossless?: boolean;
+  durationMs?: number;
 };
@@ -0,0 +1,29 @@
+import { Track } from "../entities/Track";
+
+/**
+ * Lossless with more than CD resolution (16 bit / 44.1 kHz)
+ */
+export function isHiRes(track: Track): boolean {
+  if (!track.lossless) return false;
+  return (track.bitDepth ?? 0) > 16 || (track.sampleRate ?? 0) > 44100;
+}
+
+/**
+ * Lossy codec; false when the codec is unknown
+ */
+export function isLossy(track: Track): boolean {
+  return !track.lossless && !!track.codec;
+}
+
+/**
+ * Short quality badge, e.g. "FLAC 24/96", "MP3 320", "AAC 256"
+ */
+export function qualityBadge(track: Track): string {
+  if (!track.codec) return "";
+  if (track.lossless) {
+    const depth = track.bitDepth ? `${track.bitDepth}/` : "";
+    const rate = track.sampleRate ? `${+(track.sampleRate / 1000).toFixed(1)}` : "";
+    return rate ? `${track.codec} ${depth}${rate}` : track.codec;
+  }
+  return track.bitrate ? `${track.codec} ${track.bitrate}` : track.codec;
+}
@@ -1,10 +1,219 @@
 import { invoke } from "@tauri-apps/api/core";
-import { AudioTags } from "../../domain/entities/AudioTags";
+import { AudioTags, LyricLine } from "../../domain/entities/AudioTags";
+import { ArtworkOptions } from "./picturesAdapter";
 
-export async function readAudioTags(path: string): Promise<AudioTags> {
-  return invoke<AudioTags>("read_audio_tags", { path });
+/** Without embedded artwork, `coverArt` falls back to folder artwork matching `artworkPatterns`. */
+export async function readAudioTags(path: string, artworkPatterns?: string[]): Promise<AudioTags> {
+  return invoke<AudioTags>("read_audio_tags", { path, artworkPatterns: artworkPatterns ?? null });
 }
 
-export async function writeAudioTags(path: string, tags: AudioTags): Promise<void> {
-  return invoke<void>("write_audio_tags", { path, tags });
+/** How a write treats the file's tag formats besides the fields themselves. */
+export interface TagWriteOptions {
+  id3v2_version?: "2.3" | "2.4" | null; // null = keep the file's version (2.4 for new tags)
+  id3v1?: "keep" | "sync" | "remove" | null; // "sync" rewrites ID3v1 from the new tags
+  ape?: "keep" | "sync" | "remove" | null;
+  preserve_times?: boolean; // restore the file's original mtime/atime after the write
+}
+
+/** A new `coverArt` is downscaled / re-encoded per `artwork` before it is embedded. */
+export async function writeAudioTags(
+  path: string,
+  tags: AudioTags,
+  artwork?: ArtworkOptions,
+  options?: TagWriteOptions,
+): Promise<void> {
+  return invoke<void>("write_audio_tags", { path, tags, artwork: artwork ?? null, options: options ?? null });
+}
+
+export interface TagWriteResult {
+  path: string;
+  success: boolean;
+  error: string | null;
+}
+
+export interface FilenameTagPreview {
+  path: string;
+  matched: boolean;
+  tags: AudioTags;
+}
+
+export async function previewTagsFromFilename(
+  paths: string[],
+  pattern: string,
+): Promise<FilenameTagPreview[]> {
+  return invoke<FilenameTagPreview[]>("preview_tags_from_filename", { paths, pattern });
+}
+
+export async function applyTagsFromFilename(
+  paths: string[],
+  pattern: string,
+  options?: TagWriteOptions,
+): Promise<TagWriteResult[]> {
+  return invoke<TagWriteResult[]>("apply_tags_from_filename", { paths, pattern, options: options ?? null });
+}
+
+export type FieldPatch<T> = { op: "keep" } | { op: "set"; value: T } | { op: "clear" };
+
+/**
+ * Per-field patch; fields that are omitted are kept as they are in each file.
+ * `customFields` is merged per name: `set` with `{ DJ_CUE: "32" }` only touches DJ_CUE,
+ * and an empty string removes that field. `clear` on `coverArt` removes the front cover.
+ */
+export type AudioTagsPatch = { [K in keyof AudioTags]?: FieldPatch<AudioTags[K]> };
+
+export interface TagWriteProgress {
+  current: number;
+  total: number;
+  path: string;
+}
+
+/** Progress is reported through the "tag-write-progress" event. */
+export async function writeAudioTagsBatch(
+  paths: string[],
+  patch: AudioTagsPatch,
+  artwork?: ArtworkOptions,
+  options?: TagWriteOptions,
+): Promise<TagWriteResult[]> {
+  return invoke<TagWriteResult[]>("write_audio_tags_batch", {
+    paths,
+    patch,
+    artwork: artwork ?? null,
+    options: options ?? null,
+  });
+}
+
+export interface FieldChange {
+  field: keyof AudioTags;
+  old: unknown;
+  new: unknown;
+  change: "set" | "remove";
+}
+
+export interface TagDiff {
+  path: string;
+  changes: FieldChange[];
+  error: string | null;
+}
+
+export async function previewAudioTagsWrite(path: string, tags: AudioTags): Promise<TagDiff> {
+  return invoke<TagDiff>("preview_audio_tags_write", { path, tags });
+}
+
+export async function previewAudioTagsBatch(paths: string[], patch: AudioTagsPatch): Promise<TagDiff[]> {
+  return invoke<TagDiff[]>("preview_audio_tags_batch", { paths, patch });
+}
+
+export type CleanupRuleKind =
+  | "trim"
+  | "collapseSpaces"
+  | "titleCase"
+  | "halfWidthAscii" // full-width letters, digits and symbols to ASCII
+  | "fullWidthAscii"
+  | "fullWidthKana" // half-width katakana to full-width
+  | "halfWidthKana"
+  | "featuring" // moves "(feat. X)" from the title to the artist
+  | "replace";
+
+export interface CleanupRule {
+  kind: CleanupRuleKind;
+  fields?: (keyof AudioTags)[] | null; // default: the common text fields
+  exceptions?: string[] | null; // titleCase: words written as given; default small English words
+  lowercase_rest?: boolean; // titleCase
+  keyword?: string | null; // featuring: default "feat."
+  find?: string | null; // replace: regular expression
+  replace?: string | null; // replace: `$1` etc. refer to capture groups
+  case_insensitive?: boolean;
+}
+
+/** Rules are applied in order; returns one diff per file. */
+export async function previewTagCleanup(paths: string[], rules: CleanupRule[]): Promise<TagDiff[]> {
+  return invoke<TagDiff[]>("preview_tag_cleanup", { paths, rules });
+}
+
+/** Progress is reported through the "tag-write-progress" event. */
+export async function applyTagCleanup(
+  paths: string[],
+  rules: CleanupRule[],
+  options?: TagWriteOptions,
+): Promise<TagWriteResult[]> {
+  return invoke<TagWriteResult[]>("apply_tag_cleanup", { paths, rules, options: options ?? null });
+}
+
+export interface MojibakePreview {
+  path: string;
+  changes: FieldChange[]; // fields that decode cleanly, with the repaired text
+  undecodable: (keyof AudioTags)[]; // fields that look garbled but are invalid in the chosen encoding
+  error: string | null;
+}
+
+/**
+ * `encoding` is a WHATWG label of a multi-byte encoding ("shift_jis", "euc-jp", "gbk", "big5", "euc-kr",
+ * "utf-8"); default Shift_JIS. Only text stored as Latin-1 (ID3v2 Latin-1 frames, ID3v1) is considered.
+ */
+export async function previewMojibakeRepair(paths: string[], encoding?: string): Promise<MojibakePreview[]> {
+  return invoke<MojibakePreview[]>("preview_mojibake_repair", { paths, encoding: encoding ?? null });
+}
+
+/**
+ * Re-decodes garbled tag text and writes it back as Unicode. `fields` limits the repair to the fields picked
+ * from the preview. ID3v1 cannot hold the repaired text; pass `{ id3v1: "remove" }` to drop it. Progress is
+ * reported through "tag-write-progress".
+ */
+export async function repairMojibake(
+  paths: string[],
+  encoding?: string,
+  fields?: (keyof AudioTags)[],
+  options?: TagWriteOptions,
+): Promise<TagWriteResult[]> {
+  return invoke<TagWriteResult[]>("repair_mojibake", {
+    paths,
+    encoding: encoding ?? null,
+    fields: fields ?? null,
+    options: options ?? null,
+  });
+}
+
+export interface SyncedLyrics {
+  source: "embedded" | "sidecar" | "none";
+  lines: LyricLine[];
+}
+
+export async function readSyncedLyrics(path: string): Promise<SyncedLyrics> {
+  return invoke<SyncedLyrics>("read_synced_lyrics", { path });
+}
+
+/** Embeds an .lrc file; defaults to the sidecar file next to the track. */
+export async function importLrc(path: string, lrcPath?: string, options?: TagWriteOptions): Promise<LyricLine[]> {
+  return invoke<LyricLine[]>("import_lrc", { path, lrcPath: lrcPath ?? null, options: options ?? null });
+}
+
+/** Writes the embedded synced lyrics to an .lrc file and returns its path. */
+export async function exportLrc(path: string, lrcPath?: string, overwrite = false): Promise<string> {
+  return invoke<string>("export_lrc", { path, lrcPath: lrcPath ?? null, overwrite });
+}
+
+/** Writes the embedded front cover next to the track (`cover.<ext>` by default) and returns its path. */
+export async function exportCoverToFolder(path: string, fileName?: string, overwrite = false): Promise<string> {
+  return invoke<string>("export_cover_to_folder", { path, fileName: fileName ?? null, overwrite });
+}
+
+/** Embeds the folder artwork of `directory` into every track directly in it. */
+export async function embedFolderArt(
+  directory: string,
+  options: {
+    patterns?: string[];
+    extensions?: string[];
+    overwrite?: boolean;
+    artwork?: ArtworkOptions;
+    writeOptions?: TagWriteOptions;
+  } = {},
+): Promise<TagWriteResult[]> {
+  return invoke<TagWriteResult[]>("embed_folder_art", {
+    directory,
+    patterns: options.patterns ?? null,
+    extensions: options.extensions ?? null,
+    overwrite: options.overwrite ?? false,
+    artwork: options.artwork ?? null,
+    options: options.writeOptions ?? null,
+  });
 }
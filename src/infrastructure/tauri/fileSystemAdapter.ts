@@ -28,3 +28,68 @@ export async function openPath(path: string): Promise<void> {
 export async function revealItemInDir(path: string): Promise<void> {
   return invoke<void>("reveal_item_in_dir", { path });
 }
+
+export interface TrackMove {
+  from: string;
+  to: string;
+}
+
+export interface MoveTracksResult {
+  transactionId: string;
+  moved: TrackMove[];
+  updatedPlaylists: string[];
+  journalError: string | null; // moves succeeded but cannot be undone; transactionId is empty
+}
+
+/** `pathPrefix` is the prefix "relative-from-prefix" playlists are saved with. */
+export async function moveTracks(
+  musicRoot: string,
+  playlistDir: string | null,
+  moves: TrackMove[],
+  pathPrefix: string | null = null,
+): Promise<MoveTracksResult> {
+  return invoke<MoveTracksResult>("move_tracks", { musicRoot, playlistDir, pathPrefix, moves });
+}
+
+export interface OrganizeOptions {
+  max_component_length: number | null;
+  max_path_length: number | null;
+}
+
+export interface OrganizePreviewItem {
+  source: string;
+  target: string;
+  status: "move" | "unchanged" | "collision" | "exists" | "too-long";
+}
+
+export async function previewOrganizeTracks(
+  musicRoot: string,
+  paths: string[],
+  template: string,
+  options?: OrganizeOptions,
+): Promise<OrganizePreviewItem[]> {
+  return invoke<OrganizePreviewItem[]>("preview_organize_tracks", {
+    musicRoot,
+    paths,
+    template,
+    options: options ?? null,
+  });
+}
+
+export async function organizeTracks(
+  musicRoot: string,
+  playlistDir: string | null,
+  paths: string[],
+  template: string,
+  options?: OrganizeOptions,
+  pathPrefix: string | null = null,
+): Promise<MoveTracksResult> {
+  return invoke<MoveTracksResult>("organize_tracks", {
+    musicRoot,
+    playlistDir,
+    pathPrefix,
+    paths,
+    template,
+    options: options ?? null,
+  });
+}
@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+import { TagWriteOptions } from "./audioTagsAdapter";
+
+export interface TransactionSummary {
+  id: string;
+  kind: "move" | "tag-edit";
+  label: string;
+  createdAt: number; // unix millis
+  undone: boolean;
+  fileCount: number;
+  playlistCount: number;
+}
+
+export async function listTransactions(kind?: TransactionSummary["kind"], limit?: number): Promise<TransactionSummary[]> {
+  return invoke<TransactionSummary[]>("list_transactions", {
+    kind: kind ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/** Only `preserve_times` of `options` applies; tags are restored in the formats they had. */
+export async function undoTransaction(id: string, options?: TagWriteOptions): Promise<void> {
+  return invoke<void>("undo_transaction", { id, options: options ?? null });
+}
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+import { TagWriteOptions } from "./audioTagsAdapter";
+
+export type LoudnessGroupBy = "album" | "directory" | "none";
+
+export interface LoudnessResult {
+  path: string;
+  integratedLufs: number;
+  truePeak: number; // linear, 1.0 = full scale
+  trackGain: string;
+  trackPeak: string;
+  albumGain: string;
+  albumPeak: string;
+  written: boolean;
+  error: string | null;
+}
+
+export interface LoudnessProgress {
+  jobId: string;
+  current: number;
+  total: number;
+  path: string;
+}
+
+export interface LoudnessComplete {
+  jobId: string;
+  cancelled: boolean;
+  results: LoudnessResult[];
+}
+
+/**
+ * Starts a background analysis and returns its job id. Progress is reported through
+ * the "loudness-progress" event and the results through "loudness-complete".
+ */
+export async function startLoudnessAnalysis(
+  paths: string[],
+  groupBy: LoudnessGroupBy,
+  write: boolean,
+  options?: TagWriteOptions,
+): Promise<string> {
+  return invoke<string>("start_loudness_analysis", { paths, groupBy, write, options: options ?? null });
+}
+
+export async function cancelJob(jobId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_job", { jobId });
+}
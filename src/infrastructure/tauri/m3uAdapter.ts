@@ -34,3 +34,32 @@ export async function savePlaylist(
 export async function deletePlaylist(path: string): Promise<boolean> {
   return invoke<boolean>("delete_playlist", { path });
 }
+
+export interface PathRewriteRule {
+  find: string;
+  replace: string;
+  mode: "literal" | "regex";
+  case_insensitive: boolean;
+}
+
+export interface PlaylistRewriteReport {
+  path: string;
+  total: number;
+  changed: number;
+  unresolved: number;
+  written: boolean;
+}
+
+export async function rewritePlaylistPaths(
+  root: string,
+  playlistDir: string | null,
+  rule: PathRewriteRule,
+  dryRun: boolean,
+): Promise<PlaylistRewriteReport[]> {
+  return invoke<PlaylistRewriteReport[]>("rewrite_playlist_paths", {
+    root,
+    playlistDir,
+    rule,
+    dryRun,
+  });
+}
@@ -0,0 +1,184 @@
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import { TagWriteOptions } from "./audioTagsAdapter";
+
+export type PictureType =
+  | "other"
+  | "icon"
+  | "otherIcon"
+  | "coverFront"
+  | "coverBack"
+  | "leaflet"
+  | "media"
+  | "leadArtist"
+  | "artist"
+  | "conductor"
+  | "band"
+  | "composer"
+  | "lyricist"
+  | "recordingLocation"
+  | "duringRecording"
+  | "duringPerformance"
+  | "screenCapture"
+  | "brightFish"
+  | "illustration"
+  | "bandLogo"
+  | "publisherLogo";
+
+export interface PictureInfo {
+  index: number;
+  pictureType: PictureType;
+  description: string;
+  mimeType: string;
+  width: number; // 0 if unknown
+  height: number;
+  size: number; // bytes
+}
+
+export interface PictureInput {
+  picture_type: PictureType;
+  description?: string | null;
+  data: string; // data URI
+}
+
+/** How artwork is prepared before it is embedded. */
+export interface ArtworkOptions {
+  max_dimension?: number | null; // longest side in pixels
+  jpeg_quality?: number | null; // 1-100; scaled images are re-encoded at 90 unless set
+}
+
+export async function listPictures(path: string): Promise<PictureInfo[]> {
+  return invoke<PictureInfo[]>("list_pictures", { path });
+}
+
+/** Returns the picture as a data URI. */
+export async function getPicture(path: string, index: number): Promise<string> {
+  return invoke<string>("get_picture", { path, index });
+}
+
+export async function addPicture(
+  path: string,
+  picture: PictureInput,
+  artwork?: ArtworkOptions,
+  options?: TagWriteOptions,
+): Promise<PictureInfo[]> {
+  return invoke<PictureInfo[]>("add_picture", { path, picture, artwork: artwork ?? null, options: options ?? null });
+}
+
+export async function replacePicture(
+  path: string,
+  index: number,
+  picture: PictureInput,
+  artwork?: ArtworkOptions,
+  options?: TagWriteOptions,
+): Promise<PictureInfo[]> {
+  return invoke<PictureInfo[]>("replace_picture", {
+    path,
+    index,
+    picture,
+    artwork: artwork ?? null,
+    options: options ?? null,
+  });
+}
+
+export async function removePicture(path: string, index: number, options?: TagWriteOptions): Promise<PictureInfo[]> {
+  return invoke<PictureInfo[]>("remove_picture", { path, index, options: options ?? null });
+}
+
+/** `order` lists the current picture indices in their new order. */
+export async function reorderPictures(
+  path: string,
+  order: number[],
+  options?: TagWriteOptions,
+): Promise<PictureInfo[]> {
+  return invoke<PictureInfo[]>("reorder_pictures", { path, order, options: options ?? null });
+}
+
+/** Writes the picture to `destination` (a file, or a directory) and returns the written path. */
+export async function extractPicture(path: string, index: number, destination: string, overwrite = false): Promise<string> {
+  return invoke<string>("extract_picture", { path, index, destination, overwrite });
+}
+
+export interface ArtworkStripOptions {
+  picture_types?: (PictureType | "all")[]; // empty = every type
+  min_bytes?: number | null; // only strip pictures at least this large
+}
+
+export interface ArtworkStripResult {
+  path: string;
+  removed: number;
+  bytesSaved: number;
+  error: string | null;
+}
+
+export interface ArtworkProgress {
+  current: number;
+  total: number;
+  path: string;
+}
+
+/** Strips artwork from many files; progress is reported through the "artwork-progress" event. */
+export async function stripArtwork(
+  paths: string[],
+  options: ArtworkStripOptions,
+  writeOptions?: TagWriteOptions,
+): Promise<ArtworkStripResult[]> {
+  return invoke<ArtworkStripResult[]>("strip_artwork", { paths, options, writeOptions: writeOptions ?? null });
+}
+
+export interface ArtworkNormalizeResult {
+  path: string;
+  bytesBefore: number;
+  bytesAfter: number;
+  error: string | null;
+}
+
+export interface ArtworkNormalizeProgress {
+  jobId: string;
+  current: number;
+  total: number;
+  path: string;
+}
+
+export interface ArtworkNormalizeComplete {
+  jobId: string;
+  cancelled: boolean;
+  bytesSaved: number;
+  results: ArtworkNormalizeResult[];
+}
+
+/**
+ * Starts downscaling / re-encoding the embedded pictures of `paths` in the background and
+ * returns the job id. Progress is reported through the "artwork-normalize-progress" event and
+ * the results through "artwork-normalize-complete"; cancel with `cancelJob`.
+ */
+export async function startArtworkNormalization(
+  paths: string[],
+  options: ArtworkOptions,
+  writeOptions?: TagWriteOptions,
+): Promise<string> {
+  return invoke<string>("start_artwork_normalization", { paths, options, writeOptions: writeOptions ?? null });
+}
+
+function artworkUrl(kind: "track" | "album", params: Record<string, string>, size: number, patterns?: string[]): string {
+  const query = new URLSearchParams({ ...params, size: String(size) });
+  for (const pattern of patterns ?? []) query.append("pattern", pattern);
+  return `${convertFileSrc(kind, "lochord-art")}?${query}`;
+}
+
+/**
+ * URL of a track's artwork (embedded, else folder artwork) for `<img src>`. `size` is the
+ * longest edge in pixels and is served from the on-disk thumbnail cache; 0 = original image.
+ */
+export function trackArtworkUrl(path: string, size = 256, patterns?: string[]): string {
+  return artworkUrl("track", { path }, size, patterns);
+}
+
+/** URL of an album's artwork, looked up in its directory; see `trackArtworkUrl`. */
+export function albumArtworkUrl(directory: string, size = 256, patterns?: string[]): string {
+  return artworkUrl("album", { dir: directory }, size, patterns);
+}
+
+/** Deletes every cached thumbnail and returns the number of bytes freed. */
+export async function clearThumbnailCache(): Promise<number> {
+  return invoke<number>("clear_thumbnail_cache");
+}
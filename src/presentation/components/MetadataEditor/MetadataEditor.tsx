@@ -195,7 +195,11 @@ export function MetadataEditor() {
         if (touchedFields.has("copyright")) partial.copyright = copyright;
         if (touchedFields.has("publisher")) partial.publisher = publisher;
         if (touchedFields.has("isrc")) partial.isrc = isrc;
-        if (touchedFields.has("coverArt")) partial.coverArt = coverArt;
+        if (touchedFields.has("coverArt")) {
+          partial.coverArt = coverArt;
+          // An empty cover leaves pictures alone unless removal is explicit
+          if (!coverArt) partial.removePictures = ["coverFront"];
+        }
         await updateMultipleTracksMetadata(
           selectedTracks.map((tr) => tr.absolutePath),
           partial,
@@ -222,7 +226,10 @@ export function MetadataEditor() {
           copyright,
           publisher,
           isrc,
-          coverArt,
+          // Folder artwork is only shown, never embedded implicitly
+          coverArt:
+            selectedTrack.coverArtSource === "sidecar" && coverArt === selectedTrack.coverArt ? "" : coverArt,
+          removePictures: !coverArt && selectedTrack.coverArt ? ["coverFront"] : null,
         };
         await updateTrackMetadata(selectedTrack.absolutePath, tags);
         setSaveMessage(t.metadata.saveSuccess);
@@ -246,7 +253,7 @@ export function MetadataEditor() {
   const handleCoverSelect = () => {
     const input = document.createElement("input");
     input.type = "file";
-    input.accept = "image/jpeg,image/png,image/bmp,image/gif";
+    input.accept = "image/jpeg,image/png,image/bmp,image/gif,image/tiff,image/webp";
     input.onchange = async (e) => {
       const file = (e.target as HTMLInputElement).files?.[0];
       if (!file) return;